@@ -0,0 +1,126 @@
+use darling::{FromDeriveInput, FromMeta, ast::Data, util::Flag, util::Ignored};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Expr};
+
+use crate::common::word_from_expr;
+use crate::custom_id::{CustomIdFieldOpts, generate_custom_id_impl, prefix};
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(serein), supports(struct_named, struct_unit))]
+struct RootOpts {
+	data: Data<Ignored, CustomIdFieldOpts>,
+
+	prefix: Option<String>,
+
+	label: Option<String>,
+
+	#[darling(default)]
+	style: ButtonStyle,
+
+	emoji: Option<String>,
+
+	#[darling(default)]
+	disabled: Flag,
+
+	#[darling(default)]
+	signed: Flag,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum ButtonStyle {
+	#[default]
+	Primary,
+	Secondary,
+	Success,
+	Danger,
+}
+
+impl FromMeta for ButtonStyle {
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		match word_from_expr(expr)?.as_str() {
+			"primary" => Ok(Self::Primary),
+			"secondary" => Ok(Self::Secondary),
+			"success" => Ok(Self::Success),
+			"danger" => Ok(Self::Danger),
+			other => Err(darling::Error::unknown_value(other).with_span(expr)),
+		}
+	}
+}
+
+impl ButtonStyle {
+	fn button_style_variant(&self) -> TokenStream {
+		match self {
+			Self::Primary => quote!(Primary),
+			Self::Secondary => quote!(Secondary),
+			Self::Success => quote!(Success),
+			Self::Danger => quote!(Danger),
+		}
+	}
+}
+
+pub fn derive(input: DeriveInput) -> TokenStream {
+	let root = match RootOpts::from_derive_input(&input) {
+		Ok(root) => root,
+		Err(err) => return err.write_errors(),
+	};
+
+	let prefix = match prefix(&input, root.prefix.as_ref()) {
+		Ok(prefix) => prefix,
+		Err(err) => return err,
+	};
+
+	let fn_create = generate_create(&root);
+	let signed = root.signed.is_present();
+
+	let fields = root.data.take_struct().unwrap();
+
+	let custom_id_impl = generate_custom_id_impl(&input, &prefix, &fields.fields, signed);
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote! {
+		#custom_id_impl
+
+		impl #impl_generics ::serein::component::Button for #ident #ty_generics #where_clause {
+			#fn_create
+		}
+	}
+}
+
+fn generate_create(root: &RootOpts) -> TokenStream {
+	let style = root.style.button_style_variant();
+
+	let dot_label = match &root.label {
+		Some(label) => quote! { .label(#label) },
+		None => quote! {},
+	};
+
+	let dot_emoji = match &root.emoji {
+		Some(emoji) => quote! {
+			.emoji(::serenity::all::ReactionType::Unicode(#emoji.to_owned()))
+		},
+		None => quote! {},
+	};
+
+	let dot_disabled = if root.disabled.is_present() {
+		quote! { .disabled(true) }
+	} else {
+		quote! {}
+	};
+
+	quote! {
+		fn create(&self, key: Option<&::serein::signing::SigningKey>) -> ::serein::Result<::serenity::all::CreateButton> {
+			let custom_id = <Self as ::serein::component::CustomId>::to_custom_id(self, key)?;
+
+			::serein::Result::Ok(
+				::serenity::all::CreateButton::new(custom_id)
+					.style(::serenity::all::ButtonStyle::#style)
+					#dot_label
+					#dot_emoji
+					#dot_disabled
+			)
+		}
+	}
+}
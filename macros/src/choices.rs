@@ -1,9 +1,13 @@
 use std::collections::HashMap;
 
-use darling::{FromDeriveInput, FromMeta, FromVariant, ast::Data, util::Ignored};
-use proc_macro2::TokenStream;
+use darling::{
+	FromDeriveInput, FromMeta, FromVariant,
+	ast::Data,
+	util::{Flag, Ignored},
+};
+use proc_macro2::{Literal, TokenStream};
 use quote::{ToTokens, quote};
-use syn::{DeriveInput, Expr, Ident};
+use syn::{DeriveInput, Expr, ExprUnary, Ident, Lit, UnOp};
 
 #[derive(Clone, Copy, Debug)]
 pub enum ChoiceKind {
@@ -23,6 +27,14 @@ impl ChoiceKind {
 	fn option_type_variant(&self) -> TokenStream {
 		self.resolved_value_variant()
 	}
+	fn custom_id_value_type(&self) -> TokenStream {
+		match self {
+			Self::String => quote!(String),
+			Self::Int => quote!(i64),
+			Self::Float => quote!(f64),
+		}
+	}
+
 	fn add_fn(&self) -> TokenStream {
 		match self {
 			Self::String => quote!(add_string_choice_localized),
@@ -49,6 +61,10 @@ struct VariantOpts {
 
 	#[darling(default)]
 	names: HashMap<String, String>,
+
+	desc: Option<String>,
+	emoji: Option<String>,
+	default: Flag,
 }
 
 impl VariantOpts {
@@ -74,8 +90,8 @@ impl VariantOpts {
 	fn value(&self, kind: ChoiceKind) -> Result<TokenStream, TokenStream> {
 		let value = self.value.as_ref().map(|value| match value {
 			ChoiceValue::String(value) => quote!(#value),
-			ChoiceValue::Int(value) => quote!(#value),
-			ChoiceValue::Float(value) => quote!(#value),
+			ChoiceValue::Int(value) => Literal::i64_unsuffixed(*value).into_token_stream(),
+			ChoiceValue::Float(value) => Literal::f64_unsuffixed(*value).into_token_stream(),
 		});
 
 		match kind {
@@ -104,13 +120,40 @@ impl VariantOpts {
 	}
 }
 
-#[derive(FromMeta)]
 enum ChoiceValue {
 	String(String),
 	Int(i64),
 	Float(f64),
 }
 
+impl FromMeta for ChoiceValue {
+	fn from_value(value: &Lit) -> darling::Result<Self> {
+		match value {
+			Lit::Str(lit) => Ok(Self::String(lit.value())),
+			Lit::Int(lit) => Ok(Self::Int(lit.base10_parse()?)),
+			Lit::Float(lit) => Ok(Self::Float(lit.base10_parse()?)),
+			_ => Err(darling::Error::unexpected_lit_type(value)),
+		}
+	}
+
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		match expr {
+			Expr::Lit(expr) => Self::from_value(&expr.lit),
+			Expr::Group(expr) => Self::from_expr(&expr.expr),
+			Expr::Unary(ExprUnary {
+				op: UnOp::Neg(_),
+				expr,
+				..
+			}) => match Self::from_expr(expr)? {
+				Self::Int(value) => Ok(Self::Int(-value)),
+				Self::Float(value) => Ok(Self::Float(-value)),
+				Self::String(_) => Err(darling::Error::unexpected_expr_type(expr)),
+			},
+			_ => Err(darling::Error::unexpected_expr_type(expr)),
+		}
+	}
+}
+
 pub fn derive(input: DeriveInput, kind: ChoiceKind) -> TokenStream {
 	let root = match RootOpts::from_derive_input(&input) {
 		Ok(root) => root,
@@ -132,11 +175,38 @@ pub fn derive(input: DeriveInput, kind: ChoiceKind) -> TokenStream {
 	let ident = &input.ident;
 	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
 
+	let custom_id_field_impl = match generate_custom_id_field(&variants, kind) {
+		Ok(custom_id_field_impl) => custom_id_field_impl,
+		Err(err) => return err,
+	};
+
+	let select_choice_impl = match kind {
+		ChoiceKind::String | ChoiceKind::Int => {
+			let select_choice_impl = match generate_select_choice(&variants, kind) {
+				Ok(select_choice_impl) => select_choice_impl,
+				Err(err) => return err,
+			};
+
+			quote! {
+				impl #impl_generics ::serein::component::SelectChoice for #ident #type_generics #where_clause {
+					#select_choice_impl
+				}
+			}
+		}
+		ChoiceKind::Float => quote! {},
+	};
+
 	quote! {
 		impl #impl_generics ::serein::options::CommandOption for #ident #type_generics #where_clause {
 			#fn_from_resolved_value
 			#fn_create
 		}
+
+		impl #impl_generics ::serein::component::CustomIdField for #ident #type_generics #where_clause {
+			#custom_id_field_impl
+		}
+
+		#select_choice_impl
 	}
 }
 
@@ -154,7 +224,7 @@ fn generate_from_resolved_value(
 			let value = variant.value(kind)?;
 
 			let arm = quote! {
-				#value => Self::#ident
+				#value => Ok(Self::#ident)
 			};
 
 			match_arms.push(arm);
@@ -164,7 +234,7 @@ fn generate_from_resolved_value(
 	};
 
 	Ok(quote! {
-		fn from_resolved_value(value: ::serenity::all::ResolvedValue) -> ::serein::Result<Self> {
+		fn try_from_resolved_value(value: ::serenity::all::ResolvedValue) -> ::serein::Result<Self> {
 			match value {
 				::serenity::all::ResolvedValue::#resolved_value_variant(value) => {
 					match value {
@@ -172,6 +242,7 @@ fn generate_from_resolved_value(
 						_ => Err(::serein::Error::BadOptionValue),
 					}
 				}
+				_ => Err(::serein::Error::BadOptionType),
 			}
 		}
 	})
@@ -195,13 +266,16 @@ fn generate_create(variants: &[VariantOpts], kind: ChoiceKind) -> Result<TokenSt
 				.map(|(locale, string)| quote! { (#locale, #string) })
 				.collect();
 
+			// Spelled out so that an empty array still has a known item type.
+			let localization_count = localizations.len();
+
 			let dot_choice = quote! {
 				.#add_fn(
 					#name,
 					#value,
 					[
 						#(#localizations,)*
-					]
+					] as [(&str, &str); #localization_count]
 				)
 			};
 
@@ -214,11 +288,171 @@ fn generate_create(variants: &[VariantOpts], kind: ChoiceKind) -> Result<TokenSt
 	Ok(quote! {
 		fn create(name: impl Into<String>, desc: impl Into<String>) -> ::serenity::all::CreateCommandOption {
 			::serenity::all::CreateCommandOption::new(
-				::serenity::all::CommandOptionType::#option_type_variant
+				::serenity::all::CommandOptionType::#option_type_variant,
 				name,
 				desc,
 			)
+			.required(true)
 			#(#dot_choices)*
 		}
 	})
 }
+
+fn generate_custom_id_field(
+	variants: &[VariantOpts],
+	kind: ChoiceKind,
+) -> Result<TokenStream, TokenStream> {
+	let value_type = kind.custom_id_value_type();
+
+	// Strings are percent-escaped when encoded, see the `CustomIdField` impl for `String`.
+	let max_len = match kind {
+		ChoiceKind::String => {
+			let max_len = variants
+				.iter()
+				.filter_map(|variant| match &variant.value {
+					Some(ChoiceValue::String(value)) => Some(
+						value
+							.chars()
+							.map(|c| if c == '%' || c == ':' { 3 } else { 1 })
+							.sum::<usize>(),
+					),
+					_ => None,
+				})
+				.max()
+				.unwrap_or(0);
+
+			quote! { Some(#max_len) }
+		}
+		ChoiceKind::Int => quote! { Some(20) },
+		ChoiceKind::Float => quote! { None },
+	};
+
+	let as_value = match kind {
+		ChoiceKind::String => quote! { .as_str() },
+		ChoiceKind::Int | ChoiceKind::Float => quote! {},
+	};
+
+	let mut encode_arms = Vec::<TokenStream>::new();
+	let mut decode_arms = Vec::<TokenStream>::new();
+
+	for variant in variants {
+		let ident = &variant.ident;
+		let value = variant.value(kind)?;
+
+		encode_arms.push(quote! {
+			Self::#ident => {
+				let value = #value_type::from(#value);
+				<#value_type as ::serein::component::CustomIdField>::encode(&value, out)
+			}
+		});
+
+		decode_arms.push(quote! {
+			#value => Ok(Self::#ident)
+		});
+	}
+
+	Ok(quote! {
+		const MAX_LEN: Option<usize> = #max_len;
+
+		fn encode(&self, out: &mut String) {
+			match self {
+				#(#encode_arms,)*
+			}
+		}
+
+		fn decode(segment: &str) -> ::serein::Result<Self> {
+			let value = <#value_type as ::serein::component::CustomIdField>::decode(segment)?;
+
+			match value #as_value {
+				#(#decode_arms,)*
+				_ => Err(::serein::Error::BadCustomId),
+			}
+		}
+	})
+}
+
+fn generate_select_choice(
+	variants: &[VariantOpts],
+	kind: ChoiceKind,
+) -> Result<TokenStream, TokenStream> {
+	let mut options = Vec::<TokenStream>::new();
+	let mut match_arms = Vec::<TokenStream>::new();
+
+	for variant in variants {
+		let ident = &variant.ident;
+		let name = variant.name();
+		let value = variant.value(kind)?;
+
+		let select_value = match kind {
+			ChoiceKind::String => quote! { #value },
+			ChoiceKind::Int => quote! { i64::from(#value).to_string() },
+			ChoiceKind::Float => unreachable!(),
+		};
+
+		let label_arms: Vec<TokenStream> = variant
+			.names
+			.iter()
+			.map(|(locale, string)| quote! { Some(#locale) => #string })
+			.collect();
+
+		let dot_description = match &variant.desc {
+			Some(desc) => quote! { .description(#desc) },
+			None => quote! {},
+		};
+
+		let dot_emoji = match &variant.emoji {
+			Some(emoji) => quote! {
+				.emoji(::serenity::all::ReactionType::Unicode(#emoji.to_owned()))
+			},
+			None => quote! {},
+		};
+
+		let dot_default_selection = if variant.default.is_present() {
+			quote! { .default_selection(true) }
+		} else {
+			quote! {}
+		};
+
+		options.push(quote! {
+			::serenity::all::CreateSelectMenuOption::new(
+				match locale {
+					#(#label_arms,)*
+					_ => #name,
+				},
+				#select_value,
+			)
+			#dot_description
+			#dot_emoji
+			#dot_default_selection
+		});
+
+		match_arms.push(quote! {
+			#value => Ok(Self::#ident)
+		});
+	}
+
+	let parsed_value = match kind {
+		ChoiceKind::String => quote! { value },
+		ChoiceKind::Int => quote! {
+			value
+				.parse::<i64>()
+				.map_err(|_| ::serein::Error::BadComponentValue)?
+		},
+		ChoiceKind::Float => unreachable!(),
+	};
+
+	Ok(quote! {
+		fn select_options(locale: Option<&str>) -> Vec<::serenity::all::CreateSelectMenuOption> {
+			vec![
+				#(#options,)*
+			]
+		}
+
+		fn from_select_value(value: &str) -> ::serein::Result<Self> {
+			match #parsed_value {
+				#(#match_arms,)*
+				_ => Err(::serein::Error::BadComponentValue),
+			}
+		}
+	})
+}
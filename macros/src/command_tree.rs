@@ -1,17 +1,22 @@
 use std::collections::HashMap;
 
 use darling::{
-	FromDeriveInput, FromField, FromVariant,
+	FromDeriveInput, FromField, FromMeta, FromVariant,
 	ast::{Data, Fields},
 	util::{Flag, Ignored},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Ident, Type};
+use syn::{DeriveInput, Expr, Ident, Type};
+
+use crate::common::{data_type, parse_data_type, words_from_expr};
 
 #[derive(Debug, Clone, FromDeriveInput)]
 #[darling(attributes(serein), supports(enum_newtype))]
 struct RootOpts {
+	#[darling(rename = "data", default, with = parse_data_type)]
+	data_type: Option<Type>,
+
 	data: Data<VariantOpts, Ignored>,
 }
 
@@ -22,7 +27,7 @@ struct VariantOpts {
 	fields: Fields<VariantFieldOpts>,
 
 	name: Option<String>,
-	desc: String,
+	desc: Option<String>,
 
 	#[darling(default)]
 	names: HashMap<String, String>,
@@ -32,6 +37,17 @@ struct VariantOpts {
 
 	#[darling(default)]
 	nsfw: Flag,
+
+	default_member_permissions: Option<Permissions>,
+
+	contexts: Option<Contexts>,
+	integration_types: Option<IntegrationTypes>,
+
+	#[darling(default)]
+	message: Flag,
+
+	#[darling(default)]
+	user: Flag,
 }
 
 impl VariantOpts {
@@ -66,6 +82,190 @@ impl VariantOpts {
 	pub fn ty(&self) -> &Type {
 		&self.fields.fields[0].ty
 	}
+
+	fn command_type_variant(&self) -> TokenStream {
+		if self.message.is_present() {
+			quote!(Message)
+		} else if self.user.is_present() {
+			quote!(User)
+		} else {
+			quote!(ChatInput)
+		}
+	}
+
+	fn command_trait(&self, data_type: &TokenStream) -> TokenStream {
+		if self.message.is_present() {
+			quote!(::serein::context_menu::MessageCommand<#data_type>)
+		} else if self.user.is_present() {
+			quote!(::serein::context_menu::UserCommand<#data_type>)
+		} else {
+			quote!(::serein::slash::Command<#data_type>)
+		}
+	}
+
+	fn validate(&self) -> Result<(), TokenStream> {
+		if self.message.is_present() && self.user.is_present() {
+			return Err(syn::Error::new(
+				self.ident.span(),
+				"a command cannot be both a message and a user command",
+			)
+			.into_compile_error());
+		}
+
+		let is_context_menu = self.message.is_present() || self.user.is_present();
+
+		if !is_context_menu && self.desc.is_none() {
+			return Err(syn::Error::new(
+				self.ident.span(),
+				"provide a description through an attribute",
+			)
+			.into_compile_error());
+		}
+
+		if is_context_menu && (self.desc.is_some() || !self.descs.is_empty()) {
+			return Err(syn::Error::new(
+				self.ident.span(),
+				"context menu commands cannot have a description",
+			)
+			.into_compile_error());
+		}
+
+		Ok(())
+	}
+}
+
+/// Flag names of Serenity's `Permissions`, so that typos are reported on the attribute.
+const PERMISSION_FLAGS: &[&str] = &[
+	"CREATE_INSTANT_INVITE",
+	"KICK_MEMBERS",
+	"BAN_MEMBERS",
+	"ADMINISTRATOR",
+	"MANAGE_CHANNELS",
+	"MANAGE_GUILD",
+	"ADD_REACTIONS",
+	"VIEW_AUDIT_LOG",
+	"PRIORITY_SPEAKER",
+	"STREAM",
+	"VIEW_CHANNEL",
+	"SEND_MESSAGES",
+	"SEND_TTS_MESSAGES",
+	"MANAGE_MESSAGES",
+	"EMBED_LINKS",
+	"ATTACH_FILES",
+	"READ_MESSAGE_HISTORY",
+	"MENTION_EVERYONE",
+	"USE_EXTERNAL_EMOJIS",
+	"VIEW_GUILD_INSIGHTS",
+	"CONNECT",
+	"SPEAK",
+	"MUTE_MEMBERS",
+	"DEAFEN_MEMBERS",
+	"MOVE_MEMBERS",
+	"USE_VAD",
+	"CHANGE_NICKNAME",
+	"MANAGE_NICKNAMES",
+	"MANAGE_ROLES",
+	"MANAGE_WEBHOOKS",
+	"MANAGE_GUILD_EXPRESSIONS",
+	"MANAGE_EMOJIS_AND_STICKERS",
+	"USE_APPLICATION_COMMANDS",
+	"REQUEST_TO_SPEAK",
+	"MANAGE_EVENTS",
+	"MANAGE_THREADS",
+	"CREATE_PUBLIC_THREADS",
+	"CREATE_PRIVATE_THREADS",
+	"USE_EXTERNAL_STICKERS",
+	"SEND_MESSAGES_IN_THREADS",
+	"USE_EMBEDDED_ACTIVITIES",
+	"MODERATE_MEMBERS",
+	"VIEW_CREATOR_MONETIZATION_ANALYTICS",
+	"USE_SOUNDBOARD",
+	"CREATE_GUILD_EXPRESSIONS",
+	"CREATE_EVENTS",
+	"USE_EXTERNAL_SOUNDS",
+	"SEND_VOICE_MESSAGES",
+	"SET_VOICE_CHANNEL_STATUS",
+	"SEND_POLLS",
+	"USE_EXTERNAL_APPS",
+];
+
+/// Permission flags separated by `|`. An empty string allows only administrators.
+#[derive(Debug, Clone)]
+struct Permissions(Vec<Ident>);
+
+impl FromMeta for Permissions {
+	fn from_string(value: &str) -> darling::Result<Self> {
+		let mut flags = Vec::new();
+		let mut errors = darling::Error::accumulator();
+
+		for flag in value.split('|').map(str::trim) {
+			if flag.is_empty() && value.trim().is_empty() {
+				break;
+			}
+
+			if PERMISSION_FLAGS.contains(&flag) {
+				flags.push(Ident::new(flag, proc_macro2::Span::call_site()));
+			} else {
+				errors.push(darling::Error::custom(format!(
+					"unknown permission `{flag}`, expected flags like `MANAGE_GUILD | BAN_MEMBERS`"
+				)));
+			}
+		}
+
+		errors.finish()?;
+
+		Ok(Self(flags))
+	}
+}
+
+impl Permissions {
+	fn to_tokens(&self) -> TokenStream {
+		if self.0.is_empty() {
+			return quote! { ::serenity::all::Permissions::empty() };
+		}
+
+		let flags = &self.0;
+
+		quote! { #(::serenity::all::Permissions::#flags)|* }
+	}
+}
+
+/// Where the command can be used, e.g. `contexts = [guild, bot_dm, private_channel]`.
+#[derive(Debug, Clone)]
+struct Contexts(Vec<TokenStream>);
+
+impl FromMeta for Contexts {
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		words_from_expr(expr)?
+			.iter()
+			.map(|word| match word.as_str() {
+				"guild" => Ok(quote!(Guild)),
+				"bot_dm" => Ok(quote!(BotDm)),
+				"private_channel" => Ok(quote!(PrivateChannel)),
+				other => Err(darling::Error::unknown_value(other).with_span(expr)),
+			})
+			.collect::<darling::Result<_>>()
+			.map(Self)
+	}
+}
+
+/// Where the app must be installed for the command to be available, e.g.
+/// `integration_types = [guild_install, user_install]`.
+#[derive(Debug, Clone)]
+struct IntegrationTypes(Vec<TokenStream>);
+
+impl FromMeta for IntegrationTypes {
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		words_from_expr(expr)?
+			.iter()
+			.map(|word| match word.as_str() {
+				"guild_install" => Ok(quote!(Guild)),
+				"user_install" => Ok(quote!(User)),
+				other => Err(darling::Error::unknown_value(other).with_span(expr)),
+			})
+			.collect::<darling::Result<_>>()
+			.map(Self)
+	}
 }
 
 #[derive(Debug, Clone, FromField)]
@@ -80,33 +280,45 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 		Err(err) => return err.write_errors(),
 	};
 
+	let data_type = data_type(root.data_type.as_ref());
+
 	let variants = root.data.take_enum().unwrap();
 
-	let fn_dispatch = generate_dispatch(&variants);
-	let fn_create = generate_create(&variants);
+	for variant in &variants {
+		if let Err(err) = variant.validate() {
+			return err;
+		}
+	}
+
+	let fn_dispatch = generate_dispatch(&variants, &data_type);
+	let fn_create = generate_create(&variants, &data_type);
 
 	let ident = &input.ident;
 	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
 	quote! {
 		#[::serenity::async_trait]
-		impl #impl_generics ::serein::slash::CommandTree for #ident #ty_generics #where_clause {
+		impl #impl_generics ::serein::slash::CommandTree<#data_type> for #ident #ty_generics #where_clause {
 			#fn_dispatch
 			#fn_create
 		}
 	}
 }
 
-fn generate_dispatch(variants: &[VariantOpts]) -> TokenStream {
+fn generate_dispatch(variants: &[VariantOpts], data_type: &TokenStream) -> TokenStream {
 	let match_arms = {
 		let mut match_arms = Vec::<TokenStream>::new();
 
 		for variant in variants {
 			let name = variant.name();
 			let ty = variant.ty();
+			let command_type_variant = variant.command_type_variant();
+			let command_trait = variant.command_trait(data_type);
 
 			let arm = quote! {
-				#name => <#ty as ::serein::slash::Command>::dispatch(ctx, int).await
+				(::serenity::all::CommandType::#command_type_variant, #name) => {
+					<#ty as #command_trait>::dispatch(ctx, int, data).await
+				}
 			};
 
 			match_arms.push(arm);
@@ -116,16 +328,17 @@ fn generate_dispatch(variants: &[VariantOpts]) -> TokenStream {
 	};
 
 	quote! {
-		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction) -> ::serein::Result<()> {
+		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction, data: &#data_type) -> ::serein::Result<()> {
 			let cint = match &int {
 				::serenity::all::Interaction::Autocomplete(i) => i,
 				::serenity::all::Interaction::Command(i) => i,
 				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
 			};
 
+			let command_type = cint.data.kind;
 			let command_name = cint.data.name.as_str();
 
-			match command_name {
+			match (command_type, command_name) {
 				#(#match_arms,)*
 				_ => ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
 			}
@@ -133,14 +346,19 @@ fn generate_dispatch(variants: &[VariantOpts]) -> TokenStream {
 	}
 }
 
-fn generate_create(variants: &[VariantOpts]) -> TokenStream {
+fn generate_create(variants: &[VariantOpts], data_type: &TokenStream) -> TokenStream {
 	let creates = {
 		let mut creates = Vec::<TokenStream>::new();
 
 		for variant in variants {
 			let name = variant.name();
 			let ty = variant.ty();
-			let desc = &variant.desc;
+			let command_trait = variant.command_trait(data_type);
+
+			let dot_desc = match &variant.desc {
+				Some(desc) => quote! { .description(#desc) },
+				None => quote! {},
+			};
 
 			let dot_names: Vec<TokenStream> = variant
 				.names
@@ -160,12 +378,37 @@ fn generate_create(variants: &[VariantOpts]) -> TokenStream {
 				quote! {}
 			};
 
+			let dot_default_member_permissions = match &variant.default_member_permissions {
+				Some(permissions) => {
+					let permissions = permissions.to_tokens();
+					quote! { .default_member_permissions(#permissions) }
+				}
+				None => quote! {},
+			};
+
+			let dot_contexts = match &variant.contexts {
+				Some(Contexts(contexts)) => quote! {
+					.contexts(vec![#(::serenity::all::InteractionContext::#contexts,)*])
+				},
+				None => quote! {},
+			};
+
+			let dot_integration_types = match &variant.integration_types {
+				Some(IntegrationTypes(integration_types)) => quote! {
+					.integration_types(vec![#(::serenity::all::InstallationContext::#integration_types,)*])
+				},
+				None => quote! {},
+			};
+
 			let create = quote! {
-				<#ty as ::serein::slash::Command>::create(#name)
-					.description(#desc)
+				<#ty as #command_trait>::create(#name)
+					#dot_desc
 					#(#dot_names)*
 					#(#dot_descs)*
 					#dot_nsfw
+					#dot_default_member_permissions
+					#dot_contexts
+					#dot_integration_types
 			};
 
 			creates.push(create);
@@ -2,14 +2,15 @@ use std::collections::HashMap;
 
 use darling::{FromField, FromMeta, FromVariant, ast::Fields, util::Flag};
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Ident, Type};
+use quote::{ToTokens, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Expr, ExprLit, Ident, Lit, Meta, Path, Type, Visibility};
 
-// TODO: Channel type, Autocomplete
 #[derive(Debug, Clone, FromField)]
 #[darling(attributes(serein), map = Self::after)]
 pub struct FieldOpts {
 	pub ident: Option<Ident>,
+	pub vis: Visibility,
 	pub ty: Type,
 
 	pub name: Option<String>,
@@ -29,7 +30,9 @@ pub struct FieldOpts {
 	pub min_length: Option<u16>,
 	pub max_length: Option<u16>,
 
-	pub autocomplete: Flag,
+	pub autocomplete: Option<Path>,
+
+	pub channel_types: Option<ChannelTypes>,
 }
 
 impl FieldOpts {
@@ -62,6 +65,34 @@ impl FieldOpts {
 	}
 }
 
+/// Restricts a channel option, e.g. `channel_types = [text, voice, forum]`.
+#[derive(Debug, Clone)]
+pub struct ChannelTypes(Vec<TokenStream>);
+
+impl FromMeta for ChannelTypes {
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		words_from_expr(expr)?
+			.iter()
+			.map(|word| match word.as_str() {
+				"text" => Ok(quote!(Text)),
+				"private" => Ok(quote!(Private)),
+				"voice" => Ok(quote!(Voice)),
+				"group_dm" => Ok(quote!(GroupDm)),
+				"category" => Ok(quote!(Category)),
+				"news" => Ok(quote!(News)),
+				"news_thread" => Ok(quote!(NewsThread)),
+				"public_thread" => Ok(quote!(PublicThread)),
+				"private_thread" => Ok(quote!(PrivateThread)),
+				"stage" => Ok(quote!(Stage)),
+				"directory" => Ok(quote!(Directory)),
+				"forum" => Ok(quote!(Forum)),
+				other => Err(darling::Error::unknown_value(other).with_span(expr)),
+			})
+			.collect::<darling::Result<_>>()
+			.map(Self)
+	}
+}
+
 #[derive(Debug, Clone, FromMeta)]
 pub enum IntOrFloat {
 	Int(i64),
@@ -124,6 +155,48 @@ pub struct VariantFieldOpts {
 	pub ty: Type,
 }
 
+pub fn parse_data_type(meta: &Meta) -> darling::Result<Option<Type>> {
+	let ty = match &meta.require_name_value()?.value {
+		Expr::Lit(ExprLit {
+			lit: Lit::Str(lit), ..
+		}) => lit.parse()?,
+		expr => syn::parse2(expr.to_token_stream())?,
+	};
+
+	Ok(Some(ty))
+}
+
+// Lets keyword-like attribute values be written both bare (`style = paragraph`) and quoted
+// (`style = "paragraph"`).
+pub fn word_from_expr(expr: &Expr) -> darling::Result<String> {
+	match expr {
+		Expr::Path(path) => match path.path.get_ident() {
+			Some(ident) => Ok(ident.to_string()),
+			None => Err(darling::Error::unexpected_expr_type(expr)),
+		},
+		Expr::Lit(ExprLit {
+			lit: Lit::Str(lit), ..
+		}) => Ok(lit.value()),
+		Expr::Group(group) => word_from_expr(&group.expr),
+		_ => Err(darling::Error::unexpected_expr_type(expr)),
+	}
+}
+
+pub fn words_from_expr(expr: &Expr) -> darling::Result<Vec<String>> {
+	match expr {
+		Expr::Array(array) => array.elems.iter().map(word_from_expr).collect(),
+		Expr::Group(group) => words_from_expr(&group.expr),
+		_ => Err(darling::Error::unexpected_expr_type(expr)),
+	}
+}
+
+pub fn data_type(data_type: Option<&Type>) -> TokenStream {
+	match data_type {
+		Some(ty) => quote! { #ty },
+		None => quote! { () },
+	}
+}
+
 pub fn generate_opt_creates(fields: &[FieldOpts]) -> Vec<TokenStream> {
 	let mut sub_opt_creates = Vec::<TokenStream>::new();
 
@@ -172,6 +245,12 @@ pub fn generate_opt_creates(fields: &[FieldOpts]) -> Vec<TokenStream> {
 			None => quote! {},
 		};
 
+		let dot_autocomplete = if field.autocomplete.is_some() {
+			quote! { .set_autocomplete(true) }
+		} else {
+			quote! {}
+		};
+
 		let create = quote! {
 			<#ty as ::serein::options::CommandOption>::create(#name, #desc)
 				#(#dot_names)*
@@ -181,6 +260,17 @@ pub fn generate_opt_creates(fields: &[FieldOpts]) -> Vec<TokenStream> {
 				#dot_max_value
 				#dot_min_length
 				#dot_max_length
+				#dot_autocomplete
+		};
+
+		let create = match &field.channel_types {
+			Some(ChannelTypes(channel_types)) => quote_spanned! {ty.span()=>
+				::serein::options::channel_types::<#ty>(
+					#create,
+					vec![#(::serenity::all::ChannelType::#channel_types,)*],
+				)
+			},
+			None => create,
 		};
 
 		sub_opt_creates.push(create);
@@ -205,3 +295,178 @@ pub fn generate_sub_or_subsub_create_from_struct(fields: &[FieldOpts]) -> TokenS
 		}
 	}
 }
+
+// The partial struct is declared in an anonymous const so that it can't clash with the user's
+// types, and is only reachable through `serein::slash::Partial<Cmd>`.
+pub fn generate_partial_struct(input: &DeriveInput, fields: &[FieldOpts]) -> TokenStream {
+	if !fields.iter().any(|field| field.autocomplete.is_some()) {
+		return quote! {};
+	}
+
+	let partial_fields: Vec<TokenStream> = fields
+		.iter()
+		.map(|field| {
+			let vis = &field.vis;
+			let ident = field.ident.as_ref().unwrap();
+			let ty = &field.ty;
+
+			quote! { #vis #ident: Option<#ty> }
+		})
+		.collect();
+
+	let parse_fields: Vec<TokenStream> = fields
+		.iter()
+		.map(|field| {
+			let ident = field.ident.as_ref().unwrap();
+			let name = field.name();
+			let ty = &field.ty;
+
+			quote! {
+				#ident: opts
+					.iter()
+					.filter(|opt| opt.name == #name)
+					.last()
+					.and_then(|opt| {
+						<#ty as ::serein::options::CommandOption>::try_from_resolved_value(opt.value.clone()).ok()
+					})
+			}
+		})
+		.collect();
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote! {
+		const _: () = {
+			pub struct __SereinPartial #impl_generics #where_clause {
+				#(#partial_fields,)*
+				__serein_command: ::std::marker::PhantomData<fn() -> #ident #ty_generics>,
+			}
+
+			impl #impl_generics ::serein::slash::PartialOptions for #ident #ty_generics #where_clause {
+				type Partial = __SereinPartial #ty_generics;
+
+				fn partial_from_options(opts: &[::serenity::all::ResolvedOption]) -> Self::Partial {
+					__SereinPartial {
+						#(#parse_fields,)*
+						__serein_command: ::std::marker::PhantomData,
+					}
+				}
+			}
+		};
+	}
+}
+
+// `get_opts` evaluates to the `Vec<ResolvedOption>` holding the options of this (sub)command, or
+// returns early with an error.
+pub fn generate_leaf_dispatch(
+	fields: &[FieldOpts],
+	data_type: &TokenStream,
+	get_opts: TokenStream,
+) -> TokenStream {
+	let self_fields = {
+		let mut self_fields = Vec::<TokenStream>::new();
+
+		for field in fields {
+			let ident = field.ident.as_ref().unwrap();
+			let name = field.name();
+			let ty = &field.ty;
+
+			let on_missing = if field.default.is_present() {
+				quote! {
+					<#ty as Default>::default()
+				}
+			} else {
+				quote! {
+					<#ty as ::serein::options::CommandOption>::try_from_missing_value()?
+				}
+			};
+
+			let self_field = quote! {
+				#ident: match opts.iter().filter(|opt| opt.name == #name).last() {
+					Some(opt) => {
+						<#ty as ::serein::options::CommandOption>::try_from_resolved_value(opt.value.clone())?
+					}
+					None => {
+						#on_missing
+					}
+				}
+			};
+
+			self_fields.push(self_field);
+		}
+
+		self_fields
+	};
+
+	let autocomplete_fields: Vec<&FieldOpts> = fields
+		.iter()
+		.filter(|field| field.autocomplete.is_some())
+		.collect();
+
+	let on_autocomplete = if autocomplete_fields.is_empty() {
+		quote! {
+			return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
+		}
+	} else {
+		let handler_arms: Vec<TokenStream> = autocomplete_fields
+			.iter()
+			.map(|field| {
+				let name = field.name();
+				let handler = field.autocomplete.as_ref().unwrap();
+
+				quote! {
+					#name => #handler(&ctx, &partial, &value, data).await?
+				}
+			})
+			.collect();
+
+		quote! {
+			let (partial, name, value) = {
+				let (name, value) = match cint.data.autocomplete() {
+					Some(opt) => (opt.name.to_owned(), opt.value.to_owned()),
+					None => return ::serein::Result::Err(::serein::Error::MissingOption),
+				};
+
+				let opts = #get_opts;
+
+				let partial = <Self as ::serein::slash::PartialOptions>::partial_from_options(&opts);
+
+				(partial, name, value)
+			};
+
+			let ctx = ::serein::Ctx::new(ctx, cint);
+
+			let choices = match name.as_str() {
+				#(#handler_arms,)*
+				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
+			};
+
+			return ctx.autocomplete(choices).await;
+		}
+	};
+
+	quote! {
+		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction, data: &#data_type) -> ::serein::Result<()> {
+			let cint = match int {
+				::serenity::all::Interaction::Autocomplete(cint) => {
+					#on_autocomplete
+				}
+				::serenity::all::Interaction::Command(cint) => cint,
+				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
+			};
+
+			let obj = {
+				let opts = #get_opts;
+
+				Self {
+					#(#self_fields,)*
+				}
+			};
+
+			let ctx = ::serein::Ctx::new(ctx, cint);
+
+			<Self as ::serein::slash::CommandHandler<#data_type>>::handle(&obj, ctx, data).await
+		}
+	}
+}
@@ -0,0 +1,120 @@
+use darling::{FromDeriveInput, FromField, ast::Data, util::Ignored};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, Type};
+
+use crate::common::{data_type, parse_data_type};
+
+#[derive(Clone, Copy, Debug)]
+pub enum ContextMenuKind {
+	Message,
+	User,
+}
+
+impl ContextMenuKind {
+	fn command_type_variant(&self) -> TokenStream {
+		match self {
+			Self::Message => quote!(Message),
+			Self::User => quote!(User),
+		}
+	}
+
+	fn trait_ident(&self) -> TokenStream {
+		match self {
+			Self::Message => quote!(MessageCommand),
+			Self::User => quote!(UserCommand),
+		}
+	}
+}
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(serein), supports(struct_named, struct_unit))]
+struct RootOpts {
+	#[darling(rename = "data", default, with = parse_data_type)]
+	data_type: Option<Type>,
+
+	data: Data<Ignored, FieldOpts>,
+}
+
+#[derive(FromField)]
+#[darling(attributes(serein))]
+struct FieldOpts {
+	ident: Option<Ident>,
+	ty: Type,
+}
+
+pub fn derive(input: DeriveInput, kind: ContextMenuKind) -> TokenStream {
+	let root = match RootOpts::from_derive_input(&input) {
+		Ok(root) => root,
+		Err(err) => return err.write_errors(),
+	};
+
+	let data_type = data_type(root.data_type.as_ref());
+
+	let fields = root.data.take_struct().unwrap();
+
+	let fn_dispatch = generate_dispatch(&fields.fields, &data_type);
+	let fn_create = generate_create(kind);
+
+	let trait_ident = kind.trait_ident();
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote! {
+		#[::serenity::async_trait]
+		impl #impl_generics ::serein::context_menu::#trait_ident<#data_type> for #ident #ty_generics #where_clause {
+			#fn_dispatch
+			#fn_create
+		}
+	}
+}
+
+fn generate_dispatch(fields: &[FieldOpts], data_type: &TokenStream) -> TokenStream {
+	let self_fields: Vec<TokenStream> = fields
+		.iter()
+		.map(|field| {
+			let ident = field.ident.as_ref().unwrap();
+			let ty = &field.ty;
+
+			quote! {
+				#ident: <#ty as ::serein::context_menu::CommandTarget>::try_from_resolved_target(target.clone())?
+			}
+		})
+		.collect();
+
+	quote! {
+		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction, data: &#data_type) -> ::serein::Result<()> {
+			let cint = match int {
+				::serenity::all::Interaction::Command(cint) => cint,
+				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
+			};
+
+			#[allow(unused_variables)]
+			let obj = {
+				let target = match cint.data.target() {
+					Some(target) => target,
+					None => return ::serein::Result::Err(::serein::Error::MissingOption),
+				};
+
+				Self {
+					#(#self_fields,)*
+				}
+			};
+
+			let ctx = ::serein::Ctx::new(ctx, cint);
+
+			<Self as ::serein::slash::CommandHandler<#data_type>>::handle(&obj, ctx, data).await
+		}
+	}
+}
+
+fn generate_create(kind: ContextMenuKind) -> TokenStream {
+	let command_type_variant = kind.command_type_variant();
+
+	quote! {
+		fn create(name: impl Into<String>) -> ::serenity::all::CreateCommand {
+			::serenity::all::CreateCommand::new(name)
+				.kind(::serenity::all::CommandType::#command_type_variant)
+		}
+	}
+}
@@ -0,0 +1,146 @@
+use darling::FromField;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, Type};
+
+#[derive(Debug, Clone, FromField)]
+#[darling(attributes(serein))]
+pub struct CustomIdFieldOpts {
+	pub ident: Option<Ident>,
+	pub ty: Type,
+}
+
+// Mirror `serein::component::CUSTOM_ID_MAX_LEN` and `serein::router::RESERVED_PREFIX`.
+const CUSTOM_ID_MAX_LEN: usize = 100;
+const RESERVED_PREFIX: &str = "serein";
+
+pub fn prefix(input: &DeriveInput, prefix: Option<&String>) -> Result<String, TokenStream> {
+	let prefix = prefix.cloned().unwrap_or_else(|| input.ident.to_string());
+
+	if prefix.is_empty() || prefix.contains(':') {
+		return Err(syn::Error::new(
+			input.ident.span(),
+			"the custom_id prefix must be non-empty and cannot contain `:`",
+		)
+		.into_compile_error());
+	}
+
+	if prefix == RESERVED_PREFIX {
+		return Err(syn::Error::new(
+			input.ident.span(),
+			"the custom_id prefix `serein` is reserved for serein's own components",
+		)
+		.into_compile_error());
+	}
+
+	if prefix.chars().count() > CUSTOM_ID_MAX_LEN {
+		return Err(syn::Error::new(
+			input.ident.span(),
+			"the custom_id prefix can't be longer than Discord's limit of 100 characters",
+		)
+		.into_compile_error());
+	}
+
+	Ok(prefix)
+}
+
+pub fn generate_custom_id_impl(
+	input: &DeriveInput,
+	prefix: &str,
+	fields: &[CustomIdFieldOpts],
+	signed: bool,
+) -> TokenStream {
+	let encodes: Vec<TokenStream> = fields
+		.iter()
+		.map(|field| {
+			let ident = field.ident.as_ref().unwrap();
+			let ty = &field.ty;
+
+			quote! {
+				custom_id.push(::serein::component::CUSTOM_ID_SEPARATOR);
+				<#ty as ::serein::component::CustomIdField>::encode(&self.#ident, &mut custom_id);
+			}
+		})
+		.collect();
+
+	let self_fields: Vec<TokenStream> = fields
+		.iter()
+		.map(|field| {
+			let ident = field.ident.as_ref().unwrap();
+			let ty = &field.ty;
+
+			quote! {
+				#ident: match segments.next() {
+					Some(segment) => <#ty as ::serein::component::CustomIdField>::decode(segment)?,
+					None => return ::serein::Result::Err(::serein::Error::BadCustomId),
+				}
+			}
+		})
+		.collect();
+
+	let tys: Vec<&Type> = fields.iter().map(|field| &field.ty).collect();
+
+	let (sign, verify, signature_len) = if signed {
+		(
+			quote! {
+				let custom_id = ::serein::signing::sign_custom_id(custom_id, key)?;
+			},
+			quote! {
+				let custom_id = ::serein::signing::verify_custom_id(custom_id, key)?;
+			},
+			quote! { Some(::serein::signing::SIGNATURE_LEN), },
+		)
+	} else {
+		(quote! { let _ = key; }, quote! { let _ = key; }, quote! {})
+	};
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	// Generic parameters can't be named from a free constant, so generic types are only checked at
+	// runtime.
+	let assert_fits = if input.generics.params.is_empty() {
+		quote! {
+			const _: () = ::serein::component::assert_custom_id_fits(
+				#prefix,
+				&[#(<#tys as ::serein::component::CustomIdField>::MAX_LEN,)* #signature_len],
+			);
+		}
+	} else {
+		quote! {}
+	};
+
+	quote! {
+		impl #impl_generics ::serein::component::CustomId for #ident #ty_generics #where_clause {
+			const PREFIX: &'static str = #prefix;
+
+			fn to_custom_id(&self, key: Option<&::serein::signing::SigningKey>) -> ::serein::Result<String> {
+				let mut custom_id = String::from(#prefix);
+
+				#(#encodes)*
+
+				#sign
+
+				::serein::component::check_custom_id_len(custom_id)
+			}
+
+			fn from_custom_id(custom_id: &str, key: Option<&::serein::signing::SigningKey>) -> ::serein::Result<Self> {
+				#verify
+
+				let mut segments = ::serein::component::split_custom_id(custom_id, #prefix)?;
+
+				let obj = Self {
+					#(#self_fields,)*
+				};
+
+				if segments.next().is_some() {
+					return ::serein::Result::Err(::serein::Error::BadCustomId);
+				}
+
+				::serein::Result::Ok(obj)
+			}
+		}
+
+		#assert_fits
+	}
+}
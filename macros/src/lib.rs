@@ -2,11 +2,16 @@ use proc_macro::TokenStream;
 use syn::{DeriveInput, parse_macro_input};
 
 use crate::choices::ChoiceKind;
+use crate::context_menu::ContextMenuKind;
 
+mod button;
 mod choices;
 mod command;
 mod command_tree;
 mod common;
+mod context_menu;
+mod custom_id;
+mod modal;
 mod subcommand;
 mod subsubcommand;
 
@@ -34,6 +39,30 @@ pub fn derive_subsubcommand(input: TokenStream) -> TokenStream {
 	subsubcommand::derive(input).into()
 }
 
+#[proc_macro_derive(MessageCommand, attributes(serein))]
+pub fn derive_message_command(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	context_menu::derive(input, ContextMenuKind::Message).into()
+}
+
+#[proc_macro_derive(UserCommand, attributes(serein))]
+pub fn derive_user_command(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	context_menu::derive(input, ContextMenuKind::User).into()
+}
+
+#[proc_macro_derive(Modal, attributes(serein))]
+pub fn derive_modal(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	modal::derive(input).into()
+}
+
+#[proc_macro_derive(Button, attributes(serein))]
+pub fn derive_button(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	button::derive(input).into()
+}
+
 #[proc_macro_derive(StringChoice, attributes(serein))]
 pub fn derive_string_choice(input: TokenStream) -> TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
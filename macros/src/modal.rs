@@ -0,0 +1,215 @@
+use darling::{FromDeriveInput, FromField, FromMeta, ast::Data, util::Ignored};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Expr, Ident, Type};
+
+use crate::common::word_from_expr;
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(serein), supports(struct_named))]
+struct RootOpts {
+	data: Data<Ignored, FieldOpts>,
+
+	title: String,
+}
+
+#[derive(FromField)]
+#[darling(attributes(serein))]
+struct FieldOpts {
+	ident: Option<Ident>,
+	ty: Type,
+
+	label: Option<String>,
+	placeholder: Option<String>,
+
+	#[darling(default)]
+	style: InputStyle,
+
+	min_length: Option<u16>,
+	max_length: Option<u16>,
+
+	value: Option<String>,
+}
+
+impl FieldOpts {
+	fn custom_id(&self) -> String {
+		self.ident.as_ref().unwrap().to_string()
+	}
+
+	fn label(&self) -> String {
+		self.label.clone().unwrap_or_else(|| self.custom_id())
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum InputStyle {
+	#[default]
+	Short,
+	Paragraph,
+}
+
+impl FromMeta for InputStyle {
+	fn from_expr(expr: &Expr) -> darling::Result<Self> {
+		match word_from_expr(expr)?.as_str() {
+			"short" => Ok(Self::Short),
+			"paragraph" => Ok(Self::Paragraph),
+			other => Err(darling::Error::unknown_value(other).with_span(expr)),
+		}
+	}
+}
+
+impl InputStyle {
+	fn input_text_style_variant(&self) -> TokenStream {
+		match self {
+			Self::Short => quote!(Short),
+			Self::Paragraph => quote!(Paragraph),
+		}
+	}
+}
+
+pub fn derive(input: DeriveInput) -> TokenStream {
+	let root = match RootOpts::from_derive_input(&input) {
+		Ok(root) => root,
+		Err(err) => return err.write_errors(),
+	};
+
+	let fields = root.data.take_struct().unwrap();
+
+	if fields.len() > 5 {
+		return syn::Error::new(input.ident.span(), "a modal can have at most 5 fields")
+			.into_compile_error();
+	}
+
+	let fn_create = generate_create(&fields.fields, &root.title);
+	let fn_try_from_modal_interaction = generate_try_from_modal_interaction(&fields.fields);
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote! {
+		impl #impl_generics ::serein::modal::Modal for #ident #ty_generics #where_clause {
+			#fn_create
+			#fn_try_from_modal_interaction
+		}
+	}
+}
+
+fn generate_create(fields: &[FieldOpts], title: &str) -> TokenStream {
+	let rows = {
+		let mut rows = Vec::<TokenStream>::new();
+
+		for field in fields {
+			let ty = &field.ty;
+			let custom_id = field.custom_id();
+			let label = field.label();
+			let style = field.style.input_text_style_variant();
+
+			let dot_placeholder = match &field.placeholder {
+				Some(placeholder) => quote! { .placeholder(#placeholder) },
+				None => quote! {},
+			};
+
+			let dot_min_length = match field.min_length {
+				Some(value) => quote! { .min_length(#value) },
+				None => quote! {},
+			};
+
+			let dot_max_length = match field.max_length {
+				Some(value) => quote! { .max_length(#value) },
+				None => quote! {},
+			};
+
+			let dot_value = match &field.value {
+				Some(value) => quote! { .value(#value) },
+				None => quote! {},
+			};
+
+			let row = quote! {
+				::serenity::all::CreateActionRow::InputText(
+					::serenity::all::CreateInputText::new(
+						::serenity::all::InputTextStyle::#style,
+						#label,
+						#custom_id,
+					)
+					.required(<#ty as ::serein::modal::ModalField>::REQUIRED)
+					#dot_placeholder
+					#dot_min_length
+					#dot_max_length
+					#dot_value
+				)
+			};
+
+			rows.push(row);
+		}
+
+		rows
+	};
+
+	quote! {
+		fn create(custom_id: impl Into<String>) -> ::serenity::all::CreateModal {
+			::serenity::all::CreateModal::new(custom_id, #title)
+				.components(vec![
+					#(#rows,)*
+				])
+		}
+	}
+}
+
+fn generate_try_from_modal_interaction(fields: &[FieldOpts]) -> TokenStream {
+	let self_fields = {
+		let mut self_fields = Vec::<TokenStream>::new();
+
+		for field in fields {
+			let ident = field.ident.as_ref().unwrap();
+			let ty = &field.ty;
+			let custom_id = field.custom_id();
+
+			let check_max_length = match field.max_length {
+				Some(max_length) => quote! {
+					if value.as_ref().is_some_and(|value| value.chars().count() > #max_length as usize) {
+						return ::serein::Result::Err(::serein::Error::FieldTooLong(#custom_id.to_owned()));
+					}
+				},
+				None => quote! {},
+			};
+
+			let self_field = quote! {
+				#ident: {
+					let value = int
+						.data
+						.components
+						.iter()
+						.flat_map(|row| row.components.iter())
+						.find_map(|component| match component {
+							::serenity::all::ActionRowComponent::InputText(input) if input.custom_id == #custom_id => {
+								input.value.clone()
+							}
+							_ => None,
+						})
+						.filter(|value| !value.is_empty());
+
+					#check_max_length
+
+					match <#ty as ::serein::modal::ModalField>::from_input(value) {
+						Some(value) => value,
+						None => {
+							return ::serein::Result::Err(::serein::Error::MissingField(#custom_id.to_owned()));
+						}
+					}
+				}
+			};
+
+			self_fields.push(self_field);
+		}
+
+		self_fields
+	};
+
+	quote! {
+		fn try_from_modal_interaction(int: &::serenity::all::ModalInteraction) -> ::serein::Result<Self> {
+			::serein::Result::Ok(Self {
+				#(#self_fields,)*
+			})
+		}
+	}
+}
@@ -1,13 +1,19 @@
 use darling::{FromDeriveInput, ast::Data};
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::DeriveInput;
+use syn::{DeriveInput, Type};
 
-use crate::common::{FieldOpts, VariantOpts, generate_sub_or_subsub_create_from_struct};
+use crate::common::{
+	FieldOpts, VariantOpts, data_type, generate_leaf_dispatch, generate_partial_struct,
+	generate_sub_or_subsub_create_from_struct, parse_data_type,
+};
 
 #[derive(FromDeriveInput)]
 #[darling(attributes(serein), supports(enum_newtype, struct_named, struct_unit))]
 struct RootOpts {
+	#[darling(rename = "data", default, with = parse_data_type)]
+	data_type: Option<Type>,
+
 	data: Data<VariantOpts, FieldOpts>,
 }
 
@@ -17,18 +23,21 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 		Err(err) => return err.write_errors(),
 	};
 
-	let (fn_dispatch, fn_create) = match root.data {
+	let data_type = data_type(root.data_type.as_ref());
+
+	let (fn_dispatch, fn_create, partial_struct) = match root.data {
 		Data::Enum(variants) => {
-			let fn_dispatch = generate_dispatch_from_enum(&variants);
-			let fn_create = generate_create_from_enum(&variants);
+			let fn_dispatch = generate_dispatch_from_enum(&variants, &data_type);
+			let fn_create = generate_create_from_enum(&variants, &data_type);
 
-			(fn_dispatch, fn_create)
+			(fn_dispatch, fn_create, quote! {})
 		}
 		Data::Struct(fields) => {
-			let fn_dispatch = generate_dispatch_from_struct(&fields.fields);
+			let fn_dispatch = generate_dispatch_from_struct(&fields.fields, &data_type);
 			let fn_create = generate_create_from_struct(&fields.fields);
+			let partial_struct = generate_partial_struct(&input, &fields.fields);
 
-			(fn_dispatch, fn_create)
+			(fn_dispatch, fn_create, partial_struct)
 		}
 	};
 
@@ -36,15 +45,17 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
 	quote! {
+		#partial_struct
+
 		#[::serenity::async_trait]
-		impl #impl_generics ::serein::slash::SubCommand for #ident #ty_generics #where_clause {
+		impl #impl_generics ::serein::slash::SubCommand<#data_type> for #ident #ty_generics #where_clause {
 			#fn_dispatch
 			#fn_create
 		}
 	}
 }
 
-fn generate_dispatch_from_enum(variants: &[VariantOpts]) -> TokenStream {
+fn generate_dispatch_from_enum(variants: &[VariantOpts], data_type: &TokenStream) -> TokenStream {
 	let match_arms = {
 		let mut match_arms = Vec::<TokenStream>::new();
 
@@ -53,7 +64,7 @@ fn generate_dispatch_from_enum(variants: &[VariantOpts]) -> TokenStream {
 			let ty = variant.ty();
 
 			let arm = quote! {
-				#name => <#ty as ::serein::slash::SubSubCommand>::dispatch(ctx, int).await
+				#name => <#ty as ::serein::slash::SubSubCommand<#data_type>>::dispatch(ctx, int, data).await
 			};
 
 			match_arms.push(arm);
@@ -63,7 +74,7 @@ fn generate_dispatch_from_enum(variants: &[VariantOpts]) -> TokenStream {
 	};
 
 	quote! {
-		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction) -> ::serein::Result<()> {
+		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction, data: &#data_type) -> ::serein::Result<()> {
 			let cint = match &int {
 				::serenity::all::Interaction::Autocomplete(i) => i,
 				::serenity::all::Interaction::Command(i) => i,
@@ -78,7 +89,7 @@ fn generate_dispatch_from_enum(variants: &[VariantOpts]) -> TokenStream {
 
 			match &opt.value {
 				::serenity::all::CommandDataOptionValue::SubCommandGroup(sub_opts) => {
-					if sub_opts.len() != {
+					if sub_opts.len() != 1 {
 						return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
 					}
 
@@ -97,73 +108,26 @@ fn generate_dispatch_from_enum(variants: &[VariantOpts]) -> TokenStream {
 	}
 }
 
-fn generate_dispatch_from_struct(fields: &[FieldOpts]) -> TokenStream {
-	let self_fields = {
-		let mut self_fields = Vec::<TokenStream>::new();
-
-		for field in fields {
-			let ident = field.ident.as_ref().unwrap();
-			let name = field.name();
-			let ty = &field.ty;
-
-			let on_missing = if field.default.is_present() {
-				quote! {
-					<#ty as Default>::default()
-				}
-			} else {
-				quote! {
-					<#ty as ::serein::options::CommandOption>::try_from_missing_value()?
-				}
-			};
-
-			let self_field = quote! {
-				#ident: match sub_opts.iter().filter(|opt| opt.name == #name).last() {
-					Some(opt) => {
-						<#ty as ::serein::options::CommandOption>::try_from_resolved_value(opt.value.clone())?
-					}
-					None => {
-						#on_missing
-					}
-				}
-			};
+fn generate_dispatch_from_struct(fields: &[FieldOpts], data_type: &TokenStream) -> TokenStream {
+	let get_opts = quote! {{
+		let mut opts = cint.data.options();
 
-			self_fields.push(self_field);
+		if opts.len() != 1 {
+			return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
 		}
 
-		self_fields
-	};
-
-	quote! {
-		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction) -> ::serein::Result<()> {
-			let cint = match &int {
-				::serenity::all::Interaction::Autocomplete(i) => i,
-				::serenity::all::Interaction::Command(i) => i,
-				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
-			};
-
-			if cint.data.options.len() != 1 {
+		match opts.remove(0).value {
+			::serenity::all::ResolvedValue::SubCommand(sub_opts) => sub_opts,
+			_ => {
 				return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
 			}
-
-			let opt = &cint.data.options[0];
-
-			match &opt.value {
-				::serenity::all::CommandDataOptionValue::SubCommandGroup(sub_opts) => {
-					let obj = Self {
-						#(#self_fields)*
-					};
-
-					obj.handle(ctx, int).await
-				},
-				_ => {
-					::serein::Result::Err(::serein::Error::UnrecognizedCommand)
-				}
-			}
 		}
-	}
+	}};
+
+	generate_leaf_dispatch(fields, data_type, get_opts)
 }
 
-fn generate_create_from_enum(variants: &[VariantOpts]) -> TokenStream {
+fn generate_create_from_enum(variants: &[VariantOpts], data_type: &TokenStream) -> TokenStream {
 	let sub_opt_creates = {
 		let mut sub_opt_creates = Vec::<TokenStream>::new();
 
@@ -185,7 +149,7 @@ fn generate_create_from_enum(variants: &[VariantOpts]) -> TokenStream {
 				.collect();
 
 			let create = quote! {
-				<#ty as ::serein::slash::SubSubCommand>::create(#name, #desc)
+				<#ty as ::serein::slash::SubSubCommand<#data_type>>::create(#name, #desc)
 					#(#dot_names)*
 					#(#dot_descs)*
 			};
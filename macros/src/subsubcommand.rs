@@ -1,13 +1,19 @@
 use darling::{FromDeriveInput, ast::Data, util::Ignored};
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::DeriveInput;
+use syn::{DeriveInput, Type};
 
-use crate::common::{FieldOpts, generate_sub_or_subsub_create_from_struct};
+use crate::common::{
+	FieldOpts, data_type, generate_leaf_dispatch, generate_partial_struct,
+	generate_sub_or_subsub_create_from_struct, parse_data_type,
+};
 
 #[derive(FromDeriveInput)]
 #[darling(attributes(serein), supports(enum_newtype, struct_named, struct_unit))]
 struct RootOpts {
+	#[darling(rename = "data", default, with = parse_data_type)]
+	data_type: Option<Type>,
+
 	data: Data<Ignored, FieldOpts>,
 }
 
@@ -17,97 +23,56 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 		Err(err) => return err.write_errors(),
 	};
 
+	let data_type = data_type(root.data_type.as_ref());
+
 	let fields = root.data.take_struct().unwrap();
 
-	let fn_dispatch = generate_dispatch(&fields.fields);
+	let fn_dispatch = generate_dispatch(&fields.fields, &data_type);
 	let fn_create = generate_create(&fields.fields);
+	let partial_struct = generate_partial_struct(&input, &fields.fields);
 
 	let ident = &input.ident;
 	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
 	quote! {
+		#partial_struct
+
 		#[::serenity::async_trait]
-		impl #impl_generics ::serein::slash::SubSubCommand for #ident #ty_generics #where_clause {
+		impl #impl_generics ::serein::slash::SubSubCommand<#data_type> for #ident #ty_generics #where_clause {
 			#fn_dispatch
 			#fn_create
 		}
 	}
 }
 
-fn generate_dispatch(fields: &[FieldOpts]) -> TokenStream {
-	let self_fields = {
-		let mut self_fields = Vec::<TokenStream>::new();
-
-		for field in fields {
-			let ident = field.ident.as_ref().unwrap();
-			let name = field.name();
-			let ty = &field.ty;
-
-			let on_missing = if field.default.is_present() {
-				quote! {
-					<#ty as Default>::default()
-				}
-			} else {
-				quote! {
-					<#ty as ::serein::options::CommandOption>::try_from_missing_value()?
-				}
-			};
+fn generate_dispatch(fields: &[FieldOpts], data_type: &TokenStream) -> TokenStream {
+	let get_opts = quote! {{
+		let mut opts = cint.data.options();
 
-			let self_field = quote! {
-				#ident: match sub_sub_opts.iter().filter(|opt| opt.name == #name).last() {
-					Some(opt) => {
-						<#ty as ::serein::options::CommandOption>::try_from_resolved_value(opt.value.clone())?
-					}
-					None => {
-						#on_missing
-					}
-				}
-			};
-
-			self_fields.push(self_field);
+		if opts.len() != 1 {
+			return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
 		}
 
-		self_fields
-	};
-
-	quote! {
-		async fn dispatch(ctx: ::serenity::all::Context, int: ::serenity::all::Interaction) -> ::serein::Result<()> {
-			let cint = match &int {
-				::serenity::all::Interaction::Autocomplete(i) => i,
-				::serenity::all::Interaction::Command(i) => i,
-				_ => return ::serein::Result::Err(::serein::Error::UnrecognizedCommand),
-			};
-
-			if cint.data.options.len() != 1 {
-				return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
-			}
-
-			let opt = &cint.data.options[0];
+		match opts.remove(0).value {
+			::serenity::all::ResolvedValue::SubCommandGroup(mut sub_opts) => {
+				if sub_opts.len() != 1 {
+					return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
+				}
 
-			match &opt.value {
-				::serenity::all::CommandDataOptionValue::SubCommandGroup(sub_opts) => {
-					if sub_opts.len() != 1 {
+				match sub_opts.remove(0).value {
+					::serenity::all::ResolvedValue::SubCommand(sub_sub_opts) => sub_sub_opts,
+					_ => {
 						return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
 					}
-
-					let sub_opt = &sub_opts[0];
-
-					match &sub_opt.value {
-						::serenity::all::CommandDataOptionValue::SubCommand(sub_sub_opts) => {
-							let obj = Self {
-								#(#self_fields)*
-							};
-
-							obj.handle(ctx, int).await
-						}
-					}
-				},
-				_ => {
-					::serein::Result::Err(::serein::Error::UnrecognizedCommand)
 				}
 			}
+			_ => {
+				return ::serein::Result::Err(::serein::Error::UnrecognizedCommand);
+			}
 		}
-	}
+	}};
+
+	generate_leaf_dispatch(fields, data_type, get_opts)
 }
 
 fn generate_create(fields: &[FieldOpts]) -> TokenStream {
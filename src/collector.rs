@@ -0,0 +1,224 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use serde_json::json;
+use serenity::all::{
+	ActionRow, ChannelId, ComponentInteraction, ComponentInteractionCollector, Context,
+	EditMessage, Message, MessageId, UserId,
+};
+use serenity::futures::{Stream, StreamExt, stream};
+
+use crate::component::{CustomId, disabled_components};
+use crate::error::Result;
+use crate::signing::SigningKey;
+
+/// A typed view of a component interaction, used by [`ComponentCollector`] to pick out and parse
+/// the interactions it cares about. Implemented for every [`CustomId`].
+pub trait FromComponent: Sized {
+	fn accepts_component(int: &ComponentInteraction) -> bool;
+	fn try_from_component(int: &ComponentInteraction, key: Option<&SigningKey>) -> Result<Self>;
+}
+
+impl<T: CustomId> FromComponent for T {
+	fn accepts_component(int: &ComponentInteraction) -> bool {
+		T::matches_custom_id(&int.data.custom_id)
+	}
+
+	fn try_from_component(int: &ComponentInteraction, key: Option<&SigningKey>) -> Result<Self> {
+		T::from_custom_id(&int.data.custom_id, key)
+	}
+}
+
+/// Collects the component interactions on a sent message that `P` accepts, until the timeout
+/// runs out. When it does, or when the stream is dropped before that, the message's components
+/// are disabled, unless turned off with [`disable_on_end`](Self::disable_on_end). The components are taken from the message as last
+/// seen, either when the collector was created or in the latest collected interaction.
+pub struct ComponentCollector<P> {
+	ctx: Context,
+	channel_id: ChannelId,
+	message_id: MessageId,
+	interaction_token: Option<String>,
+	components: Vec<ActionRow>,
+	timeout: Option<Duration>,
+	author_id: Option<UserId>,
+	signing_key: Option<SigningKey>,
+	disable_on_end: bool,
+	_parser: PhantomData<fn() -> P>,
+}
+
+impl<P: FromComponent + Send + 'static> ComponentCollector<P> {
+	pub fn new(ctx: &Context, message: &Message) -> Self {
+		Self {
+			ctx: ctx.clone(),
+			channel_id: message.channel_id,
+			message_id: message.id,
+			interaction_token: None,
+			components: message.components.clone(),
+			timeout: Some(Duration::from_secs(60)),
+			author_id: None,
+			signing_key: None,
+			disable_on_end: true,
+			_parser: PhantomData,
+		}
+	}
+
+	/// Disables the components through the webhook of the interaction that the message was sent in
+	/// response to, rather than through the channel. Needed for ephemeral messages, and for
+	/// messages in channels the bot can't see, like those of user-installed apps.
+	pub fn interaction_token(mut self, token: impl Into<String>) -> Self {
+		self.interaction_token = Some(token.into());
+		self
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Collects until the stream is dropped, for callers that end it on their own terms.
+	pub fn without_timeout(mut self) -> Self {
+		self.timeout = None;
+		self
+	}
+
+	pub fn author_id(mut self, author_id: UserId) -> Self {
+		self.author_id = Some(author_id);
+		self
+	}
+
+	/// Verifies the custom ids of signed components with this key.
+	pub fn signing_key(mut self, key: SigningKey) -> Self {
+		self.signing_key = Some(key);
+		self
+	}
+
+	pub fn disable_on_end(mut self, disable_on_end: bool) -> Self {
+		self.disable_on_end = disable_on_end;
+		self
+	}
+
+	/// A failure to disable the components is yielded as the last item of the stream.
+	///
+	/// Dropping the stream before it ends disables the components too, in a spawned task. A
+	/// failure to do so can't be yielded anymore, so it's only logged.
+	pub fn stream(self) -> impl Stream<Item = Result<(P, ComponentInteraction)>> + Send {
+		let mut collector = ComponentInteractionCollector::new(&self.ctx)
+			.message_id(self.message_id)
+			.filter(P::accepts_component);
+
+		if let Some(timeout) = self.timeout {
+			collector = collector.timeout(timeout);
+		}
+
+		if let Some(author_id) = self.author_id {
+			collector = collector.author_id(author_id);
+		}
+
+		let interactions = collector.stream().boxed();
+		let disabler = Disabler {
+			ctx: self.ctx,
+			channel_id: self.channel_id,
+			message_id: self.message_id,
+			interaction_token: self.interaction_token,
+			components: self.components,
+			armed: self.disable_on_end,
+		};
+
+		stream::unfold(
+			Some((disabler, interactions, self.signing_key)),
+			|state| async move {
+				let (mut disabler, mut interactions, signing_key) = state?;
+
+				match interactions.next().await {
+					Some(int) => {
+						disabler.components.clone_from(&int.message.components);
+
+						let item = P::try_from_component(&int, signing_key.as_ref())
+							.map(|parsed| (parsed, int));
+						Some((item, Some((disabler, interactions, signing_key))))
+					}
+					None => match disabler.finish().await {
+						Ok(()) => None,
+						Err(err) => Some((Err(err), None)),
+					},
+				}
+			},
+		)
+	}
+}
+
+/// Disables the components of a collected message when the collector ends, or when it's dropped
+/// before that.
+struct Disabler {
+	ctx: Context,
+	channel_id: ChannelId,
+	message_id: MessageId,
+	interaction_token: Option<String>,
+	components: Vec<ActionRow>,
+	armed: bool,
+}
+
+impl Disabler {
+	async fn finish(mut self) -> Result<()> {
+		if !std::mem::take(&mut self.armed) {
+			return Ok(());
+		}
+
+		self.disable().await
+	}
+
+	async fn disable(&self) -> Result<()> {
+		let components = disabled_components(&self.components);
+
+		match &self.interaction_token {
+			Some(token) => {
+				let edit = json!({ "components": components });
+
+				self.ctx
+					.http
+					.edit_followup_message(token, self.message_id, &edit, vec![])
+					.await
+					.map_err(Box::new)?;
+			}
+			None => {
+				let edit = EditMessage::new().components(components);
+
+				self.channel_id
+					.edit_message(&self.ctx, self.message_id, edit)
+					.await
+					.map_err(Box::new)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Drop for Disabler {
+	fn drop(&mut self) {
+		if !self.armed {
+			return;
+		}
+
+		// Without a runtime there's nothing to spawn the edit on, which only happens when the
+		// stream outlives it.
+		let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+			return;
+		};
+
+		let disabler = Disabler {
+			ctx: self.ctx.clone(),
+			channel_id: self.channel_id,
+			message_id: self.message_id,
+			interaction_token: self.interaction_token.take(),
+			components: std::mem::take(&mut self.components),
+			armed: false,
+		};
+
+		runtime.spawn(async move {
+			if let Err(err) = disabler.disable().await {
+				tracing::warn!(error = %err, "failed to disable the components of a dropped collector");
+			}
+		});
+	}
+}
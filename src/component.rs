@@ -0,0 +1,773 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use serenity::all::{
+	ActionRow, ActionRowComponent, ChannelId, ChannelType, CommandId, ComponentInteraction,
+	ComponentInteractionDataKind, ComponentType, Context, CreateActionRow, CreateButton,
+	CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, GenericId, GuildId, MessageId,
+	RoleId, SelectMenu, UserId,
+};
+
+use crate::error::{Error, Result};
+use crate::signing::SigningKey;
+
+pub use serein_macros::Button;
+
+pub const CUSTOM_ID_MAX_LEN: usize = 100;
+pub const CUSTOM_ID_SEPARATOR: char = ':';
+
+/// Handles component interactions whose custom_id starts with the prefix the handler is
+/// registered under in an [`InteractionRouter`](crate::router::InteractionRouter).
+#[async_trait]
+pub trait ComponentHandler<D: Send + Sync = ()>: Send + Sync {
+	async fn handle(&self, ctx: Context, int: ComponentInteraction, data: &D) -> Result<()>;
+}
+
+/// Handles the interactions of a typed component, parsed from its custom_id. Everything needed
+/// lives in the custom_id, so this keeps working for messages sent before a restart. Register it
+/// with [`InteractionRouter::persistent`](crate::router::InteractionRouter::persistent).
+#[async_trait]
+#[diagnostic::on_unimplemented(
+	message = "`{Self}` has no persistent component handler",
+	label = "missing `PersistentComponent` implementation",
+	note = "implement `serein::component::PersistentComponent` for `{Self}` to handle its interactions"
+)]
+pub trait PersistentComponent<D: Send + Sync = ()>: CustomId + Send + Sync {
+	async fn handle(&self, ctx: Context, int: ComponentInteraction, data: &D) -> Result<()>;
+}
+
+// Like `ComponentHandler`, but also given the router's signing key to parse the custom_id with.
+#[async_trait]
+pub(crate) trait PersistentRoute<D: Send + Sync>: Send + Sync {
+	async fn handle(
+		&self,
+		ctx: Context,
+		int: ComponentInteraction,
+		data: &D,
+		key: Option<&SigningKey>,
+	) -> Result<()>;
+}
+
+pub(crate) struct PersistentHandler<T>(pub(crate) PhantomData<fn() -> T>);
+
+#[async_trait]
+impl<T, D> PersistentRoute<D> for PersistentHandler<T>
+where
+	T: PersistentComponent<D>,
+	D: Send + Sync,
+{
+	async fn handle(
+		&self,
+		ctx: Context,
+		int: ComponentInteraction,
+		data: &D,
+		key: Option<&SigningKey>,
+	) -> Result<()> {
+		let component = T::try_from_component_interaction(&int, key)?;
+		component.handle(ctx, int, data).await
+	}
+}
+
+/// State that is encoded into, and parsed back from, a component's `custom_id`. The id is the
+/// `PREFIX` followed by each encoded field, all separated by [`CUSTOM_ID_SEPARATOR`].
+///
+/// `key` is only used by types derived with `#[serein(signed)]`, which fail without one.
+pub trait CustomId: Sized {
+	const PREFIX: &'static str;
+
+	fn to_custom_id(&self, key: Option<&SigningKey>) -> Result<String>;
+	fn from_custom_id(custom_id: &str, key: Option<&SigningKey>) -> Result<Self>;
+
+	fn matches_custom_id(custom_id: &str) -> bool {
+		custom_id
+			.split(CUSTOM_ID_SEPARATOR)
+			.next()
+			.is_some_and(|prefix| prefix == Self::PREFIX)
+	}
+
+	fn try_from_component_interaction(
+		int: &ComponentInteraction,
+		key: Option<&SigningKey>,
+	) -> Result<Self> {
+		Self::from_custom_id(&int.data.custom_id, key)
+	}
+}
+
+pub trait Button: CustomId {
+	fn create(&self, key: Option<&SigningKey>) -> Result<CreateButton>;
+}
+
+/// A choice enum that can be offered in a string select menu. Implemented by the `StringChoice`
+/// and `IntChoice` derives, so the same enum works as a slash command option and a dropdown.
+pub trait SelectChoice: Sized {
+	/// The options of the select menu, labeled with the names for `locale` where available.
+	fn select_options(locale: Option<&str>) -> Vec<CreateSelectMenuOption>;
+	fn from_select_value(value: &str) -> Result<Self>;
+
+	fn create_select_menu(custom_id: impl Into<String>, locale: Option<&str>) -> CreateSelectMenu {
+		CreateSelectMenu::new(
+			custom_id,
+			CreateSelectMenuKind::String {
+				options: Self::select_options(locale),
+			},
+		)
+	}
+
+	fn try_from_component_interaction(int: &ComponentInteraction) -> Result<Vec<Self>> {
+		match &int.data.kind {
+			ComponentInteractionDataKind::StringSelect { values } => values
+				.iter()
+				.map(|value| Self::from_select_value(value))
+				.collect(),
+			_ => Err(Error::BadComponentType),
+		}
+	}
+}
+
+/// An entity that can be picked in one of Discord's auto-populated select menus. Mentionable
+/// selects resolve to [`GenericId`], since they mix users and roles.
+pub trait SelectEntity: Sized {
+	fn select_menu_kind() -> CreateSelectMenuKind;
+	fn try_from_component_data(kind: &ComponentInteractionDataKind) -> Result<Vec<Self>>;
+
+	fn try_from_component_interaction(int: &ComponentInteraction) -> Result<Vec<Self>> {
+		Self::try_from_component_data(&int.data.kind)
+	}
+}
+
+impl SelectEntity for UserId {
+	fn select_menu_kind() -> CreateSelectMenuKind {
+		CreateSelectMenuKind::User {
+			default_users: None,
+		}
+	}
+
+	fn try_from_component_data(kind: &ComponentInteractionDataKind) -> Result<Vec<Self>> {
+		match kind {
+			ComponentInteractionDataKind::UserSelect { values } => Ok(values.clone()),
+			_ => Err(Error::BadComponentType),
+		}
+	}
+}
+
+impl SelectEntity for RoleId {
+	fn select_menu_kind() -> CreateSelectMenuKind {
+		CreateSelectMenuKind::Role {
+			default_roles: None,
+		}
+	}
+
+	fn try_from_component_data(kind: &ComponentInteractionDataKind) -> Result<Vec<Self>> {
+		match kind {
+			ComponentInteractionDataKind::RoleSelect { values } => Ok(values.clone()),
+			_ => Err(Error::BadComponentType),
+		}
+	}
+}
+
+impl SelectEntity for ChannelId {
+	fn select_menu_kind() -> CreateSelectMenuKind {
+		CreateSelectMenuKind::Channel {
+			channel_types: None,
+			default_channels: None,
+		}
+	}
+
+	fn try_from_component_data(kind: &ComponentInteractionDataKind) -> Result<Vec<Self>> {
+		match kind {
+			ComponentInteractionDataKind::ChannelSelect { values } => Ok(values.clone()),
+			_ => Err(Error::BadComponentType),
+		}
+	}
+}
+
+impl SelectEntity for GenericId {
+	fn select_menu_kind() -> CreateSelectMenuKind {
+		CreateSelectMenuKind::Mentionable {
+			default_users: None,
+			default_roles: None,
+		}
+	}
+
+	fn try_from_component_data(kind: &ComponentInteractionDataKind) -> Result<Vec<Self>> {
+		match kind {
+			ComponentInteractionDataKind::MentionableSelect { values } => Ok(values.clone()),
+			_ => Err(Error::BadComponentType),
+		}
+	}
+}
+
+/// Builder for a user, role, channel or mentionable select menu, depending on `T`.
+#[derive(Clone, Debug)]
+pub struct EntitySelectMenu<T: SelectEntity> {
+	custom_id: String,
+	kind: CreateSelectMenuKind,
+	placeholder: Option<String>,
+	min_values: Option<u8>,
+	max_values: Option<u8>,
+	disabled: bool,
+	_entity: PhantomData<T>,
+}
+
+impl<T: SelectEntity> EntitySelectMenu<T> {
+	pub fn new(custom_id: impl Into<String>) -> Self {
+		Self {
+			custom_id: custom_id.into(),
+			kind: T::select_menu_kind(),
+			placeholder: None,
+			min_values: None,
+			max_values: None,
+			disabled: false,
+			_entity: PhantomData,
+		}
+	}
+
+	pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+		self.placeholder = Some(placeholder.into());
+		self
+	}
+
+	pub fn min_values(mut self, min_values: u8) -> Self {
+		self.min_values = Some(min_values);
+		self
+	}
+
+	pub fn max_values(mut self, max_values: u8) -> Self {
+		self.max_values = Some(max_values);
+		self
+	}
+
+	pub fn disabled(mut self, disabled: bool) -> Self {
+		self.disabled = disabled;
+		self
+	}
+
+	pub fn build(self) -> CreateSelectMenu {
+		let mut menu = CreateSelectMenu::new(self.custom_id, self.kind).disabled(self.disabled);
+
+		if let Some(placeholder) = self.placeholder {
+			menu = menu.placeholder(placeholder);
+		}
+
+		if let Some(min_values) = self.min_values {
+			menu = menu.min_values(min_values);
+		}
+
+		if let Some(max_values) = self.max_values {
+			menu = menu.max_values(max_values);
+		}
+
+		menu
+	}
+}
+
+impl EntitySelectMenu<UserId> {
+	pub fn default_users(mut self, users: Vec<UserId>) -> Self {
+		if let CreateSelectMenuKind::User { default_users } = &mut self.kind {
+			*default_users = Some(users);
+		}
+		self
+	}
+}
+
+impl EntitySelectMenu<RoleId> {
+	pub fn default_roles(mut self, roles: Vec<RoleId>) -> Self {
+		if let CreateSelectMenuKind::Role { default_roles } = &mut self.kind {
+			*default_roles = Some(roles);
+		}
+		self
+	}
+}
+
+impl EntitySelectMenu<ChannelId> {
+	pub fn channel_types(mut self, types: Vec<ChannelType>) -> Self {
+		if let CreateSelectMenuKind::Channel { channel_types, .. } = &mut self.kind {
+			*channel_types = Some(types);
+		}
+		self
+	}
+
+	pub fn default_channels(mut self, channels: Vec<ChannelId>) -> Self {
+		if let CreateSelectMenuKind::Channel {
+			default_channels, ..
+		} = &mut self.kind
+		{
+			*default_channels = Some(channels);
+		}
+		self
+	}
+}
+
+impl EntitySelectMenu<GenericId> {
+	pub fn default_users(mut self, users: Vec<UserId>) -> Self {
+		if let CreateSelectMenuKind::Mentionable { default_users, .. } = &mut self.kind {
+			*default_users = Some(users);
+		}
+		self
+	}
+
+	pub fn default_roles(mut self, roles: Vec<RoleId>) -> Self {
+		if let CreateSelectMenuKind::Mentionable { default_roles, .. } = &mut self.kind {
+			*default_roles = Some(roles);
+		}
+		self
+	}
+}
+
+/// Rebuilds the components of a sent message with every button and select menu disabled.
+/// Default values of entity select menus aren't part of the received message, so they're lost.
+pub fn disabled_components(rows: &[ActionRow]) -> Vec<CreateActionRow> {
+	let mut disabled_rows = Vec::new();
+	let mut buttons = Vec::new();
+
+	for row in rows {
+		for component in &row.components {
+			match component {
+				ActionRowComponent::Button(button) => {
+					buttons.push(CreateButton::from(button.clone()).disabled(true));
+				}
+				ActionRowComponent::SelectMenu(menu) => {
+					if let Some(menu) = disabled_select_menu(menu) {
+						disabled_rows.push(CreateActionRow::SelectMenu(menu));
+					}
+				}
+				_ => {}
+			}
+		}
+
+		if !buttons.is_empty() {
+			disabled_rows.push(CreateActionRow::Buttons(std::mem::take(&mut buttons)));
+		}
+	}
+
+	disabled_rows
+}
+
+fn disabled_select_menu(menu: &SelectMenu) -> Option<CreateSelectMenu> {
+	let custom_id = menu.custom_id.clone()?;
+
+	let kind = match menu.kind {
+		ComponentType::StringSelect => CreateSelectMenuKind::String {
+			options: menu
+				.options
+				.iter()
+				.map(|option| {
+					let mut create = CreateSelectMenuOption::new(&option.label, &option.value)
+						.default_selection(option.default);
+
+					if let Some(description) = &option.description {
+						create = create.description(description);
+					}
+
+					if let Some(emoji) = &option.emoji {
+						create = create.emoji(emoji.clone());
+					}
+
+					create
+				})
+				.collect(),
+		},
+		ComponentType::UserSelect => UserId::select_menu_kind(),
+		ComponentType::RoleSelect => RoleId::select_menu_kind(),
+		ComponentType::MentionableSelect => GenericId::select_menu_kind(),
+		ComponentType::ChannelSelect => CreateSelectMenuKind::Channel {
+			channel_types: Some(menu.channel_types.clone()),
+			default_channels: None,
+		},
+		_ => return None,
+	};
+
+	let mut create = CreateSelectMenu::new(custom_id, kind).disabled(true);
+
+	if let Some(placeholder) = &menu.placeholder {
+		create = create.placeholder(placeholder);
+	}
+
+	if let Some(min_values) = menu.min_values {
+		create = create.min_values(min_values);
+	}
+
+	if let Some(max_values) = menu.max_values {
+		create = create.max_values(max_values);
+	}
+
+	Some(create)
+}
+
+/// A single field of a [`CustomId`]. `MAX_LEN` is the longest encoding of the field, if bounded,
+/// and lets the derives reject ids that could exceed [`CUSTOM_ID_MAX_LEN`] at compile time.
+pub trait CustomIdField: Sized {
+	const MAX_LEN: Option<usize>;
+
+	fn encode(&self, out: &mut String);
+	fn decode(segment: &str) -> Result<Self>;
+}
+
+pub fn check_custom_id_len(custom_id: String) -> Result<String> {
+	if custom_id.chars().count() > CUSTOM_ID_MAX_LEN {
+		return Err(Error::CustomIdTooLong);
+	}
+
+	Ok(custom_id)
+}
+
+/// Checks the prefix of the custom id and returns the remaining encoded fields.
+pub fn split_custom_id<'a>(
+	custom_id: &'a str,
+	prefix: &str,
+) -> Result<impl Iterator<Item = &'a str>> {
+	let mut segments = custom_id.split(CUSTOM_ID_SEPARATOR);
+
+	match segments.next() {
+		Some(segment) if segment == prefix => Ok(segments),
+		_ => Err(Error::BadCustomId),
+	}
+}
+
+/// Panics, failing const evaluation, if a custom id with the given prefix and fields could
+/// exceed [`CUSTOM_ID_MAX_LEN`] characters. Fields with an unbounded length are counted as empty,
+/// so such ids only fail here if they're always too long, and are otherwise checked at runtime.
+pub const fn assert_custom_id_fits(prefix: &str, fields: &[Option<usize>]) {
+	let mut len = const_char_count(prefix);
+	let mut bounded = true;
+	let mut i = 0;
+
+	while i < fields.len() {
+		len += 1;
+		match fields[i] {
+			Some(field_len) => len += field_len,
+			None => bounded = false,
+		}
+		i += 1;
+	}
+
+	if len > CUSTOM_ID_MAX_LEN {
+		if bounded {
+			panic!("the encoded custom_id can exceed Discord's limit of 100 characters");
+		} else {
+			panic!("the encoded custom_id always exceeds Discord's limit of 100 characters");
+		}
+	}
+}
+
+// `str::chars` isn't const, so this counts the bytes that start a UTF-8 sequence instead.
+const fn const_char_count(string: &str) -> usize {
+	let bytes = string.as_bytes();
+	let mut count = 0;
+	let mut i = 0;
+
+	while i < bytes.len() {
+		if bytes[i] & 0xC0 != 0x80 {
+			count += 1;
+		}
+		i += 1;
+	}
+
+	count
+}
+
+macro_rules! impl_custom_id_field_integer {
+	($t:ty, $max_len:expr) => {
+		impl CustomIdField for $t {
+			const MAX_LEN: Option<usize> = Some($max_len);
+
+			fn encode(&self, out: &mut String) {
+				out.push_str(&self.to_string());
+			}
+
+			fn decode(segment: &str) -> Result<Self> {
+				segment.parse().map_err(|_| Error::BadCustomId)
+			}
+		}
+	};
+}
+
+impl_custom_id_field_integer!(isize, 20);
+impl_custom_id_field_integer!(i128, 40);
+impl_custom_id_field_integer!(i64, 20);
+impl_custom_id_field_integer!(i32, 11);
+impl_custom_id_field_integer!(i16, 6);
+impl_custom_id_field_integer!(i8, 4);
+impl_custom_id_field_integer!(usize, 20);
+impl_custom_id_field_integer!(u128, 39);
+impl_custom_id_field_integer!(u64, 20);
+impl_custom_id_field_integer!(u32, 10);
+impl_custom_id_field_integer!(u16, 5);
+impl_custom_id_field_integer!(u8, 3);
+
+macro_rules! impl_custom_id_field_float {
+	($t:ty) => {
+		impl CustomIdField for $t {
+			const MAX_LEN: Option<usize> = None;
+
+			fn encode(&self, out: &mut String) {
+				out.push_str(&self.to_string());
+			}
+
+			fn decode(segment: &str) -> Result<Self> {
+				segment.parse().map_err(|_| Error::BadCustomId)
+			}
+		}
+	};
+}
+
+impl_custom_id_field_float!(f64);
+impl_custom_id_field_float!(f32);
+
+impl CustomIdField for bool {
+	const MAX_LEN: Option<usize> = Some(1);
+
+	fn encode(&self, out: &mut String) {
+		out.push(if *self { '1' } else { '0' });
+	}
+
+	fn decode(segment: &str) -> Result<Self> {
+		match segment {
+			"1" => Ok(true),
+			"0" => Ok(false),
+			_ => Err(Error::BadCustomId),
+		}
+	}
+}
+
+// Strings are percent-escaped so they can't contain the separator.
+impl CustomIdField for String {
+	const MAX_LEN: Option<usize> = None;
+
+	fn encode(&self, out: &mut String) {
+		for c in self.chars() {
+			match c {
+				'%' => out.push_str("%25"),
+				CUSTOM_ID_SEPARATOR => out.push_str("%3A"),
+				c => out.push(c),
+			}
+		}
+	}
+
+	fn decode(segment: &str) -> Result<Self> {
+		let mut decoded = String::with_capacity(segment.len());
+		let mut rest = segment;
+
+		while let Some(i) = rest.find('%') {
+			decoded.push_str(&rest[..i]);
+
+			match rest.get(i..i + 3) {
+				Some("%25") => decoded.push('%'),
+				Some("%3A") => decoded.push(CUSTOM_ID_SEPARATOR),
+				_ => return Err(Error::BadCustomId),
+			}
+
+			rest = &rest[i + 3..];
+		}
+
+		decoded.push_str(rest);
+
+		Ok(decoded)
+	}
+}
+
+macro_rules! impl_custom_id_field_id {
+	($t:ty) => {
+		impl CustomIdField for $t {
+			const MAX_LEN: Option<usize> = Some(20);
+
+			fn encode(&self, out: &mut String) {
+				self.get().encode(out);
+			}
+
+			fn decode(segment: &str) -> Result<Self> {
+				match u64::decode(segment)? {
+					0 => Err(Error::BadCustomId),
+					id => Ok(Self::new(id)),
+				}
+			}
+		}
+	};
+}
+
+impl_custom_id_field_id!(UserId);
+impl_custom_id_field_id!(RoleId);
+impl_custom_id_field_id!(ChannelId);
+impl_custom_id_field_id!(GuildId);
+impl_custom_id_field_id!(MessageId);
+impl_custom_id_field_id!(CommandId);
+impl_custom_id_field_id!(GenericId);
+
+// `None` is encoded as an empty segment, and `Some` with a leading `+` so that it can be told
+// apart from `None` even when the inner value encodes to an empty string.
+impl<T: CustomIdField> CustomIdField for Option<T> {
+	const MAX_LEN: Option<usize> = match T::MAX_LEN {
+		Some(len) => Some(len + 1),
+		None => None,
+	};
+
+	fn encode(&self, out: &mut String) {
+		if let Some(value) = self {
+			out.push('+');
+			value.encode(out);
+		}
+	}
+
+	fn decode(segment: &str) -> Result<Self> {
+		match segment.strip_prefix('+') {
+			Some(segment) => T::decode(segment).map(Some),
+			None if segment.is_empty() => Ok(None),
+			None => Err(Error::BadCustomId),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip<T: CustomIdField + PartialEq + std::fmt::Debug>(value: T) -> String {
+		let mut encoded = String::new();
+		value.encode(&mut encoded);
+		assert_eq!(T::decode(&encoded).unwrap(), value, "{encoded}");
+		encoded
+	}
+
+	#[derive(Button, Debug, PartialEq)]
+	#[serein(prefix = "vote")]
+	struct Vote {
+		poll: u32,
+		option: String,
+		user: Option<UserId>,
+		up: bool,
+	}
+
+	#[derive(Button, Debug, PartialEq)]
+	struct Refresh;
+
+	#[test]
+	fn strings_are_escaped() {
+		assert_eq!(round_trip(String::from("a:b%c")), "a%3Ab%25c");
+		assert_eq!(round_trip(String::new()), "");
+		assert_eq!(round_trip(String::from("%3A")), "%253A");
+	}
+
+	#[test]
+	fn bad_escapes_are_rejected() {
+		for segment in ["%", "%3", "%3a", "%ZZ", "a%2"] {
+			assert!(String::decode(segment).is_err(), "{segment}");
+		}
+	}
+
+	#[test]
+	fn options_are_marked() {
+		assert_eq!(round_trip(None::<String>), "");
+		assert_eq!(round_trip(Some(String::new())), "+");
+		assert_eq!(round_trip(Some(5u8)), "+5");
+		assert_eq!(round_trip(Some(None::<u8>)), "+");
+		assert!(Option::<u8>::decode("5").is_err());
+		assert!(Option::<u8>::decode("+").is_err());
+	}
+
+	#[test]
+	fn numbers_and_ids_round_trip() {
+		assert_eq!(round_trip(u64::MAX), "18446744073709551615");
+		assert_eq!(round_trip(i64::MIN), "-9223372036854775808");
+		assert_eq!(round_trip(1.5f64), "1.5");
+		assert_eq!(round_trip(true), "1");
+		assert_eq!(round_trip(false), "0");
+		assert_eq!(round_trip(UserId::new(42)), "42");
+
+		assert!(u8::decode("256").is_err());
+		assert!(u8::decode("").is_err());
+		assert!(bool::decode("true").is_err());
+		assert!(UserId::decode("0").is_err());
+	}
+
+	#[test]
+	fn derived_ids_round_trip() {
+		let vote = Vote {
+			poll: 7,
+			option: String::from("yes: definitely"),
+			user: Some(UserId::new(9)),
+			up: true,
+		};
+
+		let custom_id = vote.to_custom_id(None).unwrap();
+		assert_eq!(custom_id, "vote:7:yes%3A definitely:+9:1");
+		assert!(Vote::matches_custom_id(&custom_id));
+		assert_eq!(Vote::from_custom_id(&custom_id, None).unwrap(), vote);
+
+		assert_eq!(Refresh.to_custom_id(None).unwrap(), "Refresh");
+		assert_eq!(Refresh::from_custom_id("Refresh", None).unwrap(), Refresh);
+	}
+
+	#[test]
+	fn malformed_ids_are_rejected() {
+		for custom_id in [
+			"",
+			"vote",
+			"vote:7:yes::",
+			"vote:7:yes::1:extra",
+			"vote:x:yes::1",
+			"vote:7:yes:9:1",
+			"votes:7:yes::1",
+			"Vote:7:yes::1",
+		] {
+			assert!(
+				Vote::from_custom_id(custom_id, None).is_err(),
+				"{custom_id}"
+			);
+		}
+
+		assert!(!Vote::matches_custom_id("votes:7:yes::1"));
+		assert!(Refresh::from_custom_id("Refresh:", None).is_err());
+		assert!(Refresh::from_custom_id("Refresh:1", None).is_err());
+	}
+
+	#[test]
+	fn long_ids_are_rejected() {
+		let vote = Vote {
+			poll: 1,
+			option: "x".repeat(CUSTOM_ID_MAX_LEN),
+			user: None,
+			up: false,
+		};
+
+		assert!(matches!(
+			vote.to_custom_id(None),
+			Err(Error::CustomIdTooLong)
+		));
+	}
+
+	#[test]
+	fn bounded_ids_fit() {
+		assert_custom_id_fits(
+			"vote",
+			&[u32::MAX_LEN, Option::<UserId>::MAX_LEN, bool::MAX_LEN],
+		);
+		assert_custom_id_fits(&"x".repeat(CUSTOM_ID_MAX_LEN), &[]);
+		assert_custom_id_fits(
+			&"x".repeat(CUSTOM_ID_MAX_LEN - 4),
+			&[String::MAX_LEN, Some(1)],
+		);
+		// Counted in characters, like the runtime check, not in bytes.
+		assert_custom_id_fits(&"é".repeat(CUSTOM_ID_MAX_LEN - 2), &[bool::MAX_LEN]);
+	}
+
+	#[test]
+	#[should_panic(expected = "always exceeds Discord's limit")]
+	fn unbounded_ids_that_are_always_too_long_do_not_fit() {
+		assert_custom_id_fits(
+			&"x".repeat(CUSTOM_ID_MAX_LEN - 4),
+			&[u64::MAX_LEN, String::MAX_LEN],
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "always exceeds Discord's limit")]
+	fn unbounded_ids_count_their_separators() {
+		assert_custom_id_fits(&"x".repeat(CUSTOM_ID_MAX_LEN), &[String::MAX_LEN]);
+	}
+
+	#[test]
+	#[should_panic(expected = "can exceed Discord's limit")]
+	fn oversized_ids_do_not_fit() {
+		assert_custom_id_fits("vote", &[u128::MAX_LEN, u128::MAX_LEN, u128::MAX_LEN]);
+	}
+}
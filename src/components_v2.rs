@@ -0,0 +1,552 @@
+//! Discord's layout components: containers, sections, text displays, separators, media galleries
+//! and files. Serenity 0.12 has no builders for these, so the payloads are built here and sent
+//! through [`Ctx`](crate::Ctx)'s `*_components_v2` methods, which set the `IS_COMPONENTS_V2`
+//! message flag.
+//!
+//! Serenity 0.12 also can't deserialize messages that contain these components, which includes
+//! the message attached to a component interaction on them, so such interactions never reach the
+//! event handler. The layouts therefore can't hold buttons or select menus until serenity supports
+//! them; send those in a separate message.
+
+use serde_json::{Value, json};
+use serenity::all::Colour;
+
+use crate::error::{Error, Result};
+use crate::layout::LayoutLimit;
+
+pub const IS_COMPONENTS_V2: u64 = 1 << 15;
+pub const EPHEMERAL: u64 = 1 << 6;
+
+pub const MAX_COMPONENTS: usize = 40;
+pub const MAX_TEXT_LENGTH: usize = 4000;
+pub const MAX_SECTION_TEXTS: usize = 3;
+pub const MAX_GALLERY_ITEMS: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct TextDisplay {
+	content: String,
+}
+
+impl TextDisplay {
+	pub fn new(content: impl Into<String>) -> Self {
+		Self {
+			content: content.into(),
+		}
+	}
+
+	fn to_json(&self) -> Value {
+		json!({
+			"type": 10,
+			"content": self.content,
+		})
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Media {
+	url: String,
+	description: Option<String>,
+	spoiler: bool,
+}
+
+impl Media {
+	/// `url` can also refer to an uploaded file as `attachment://<filename>`.
+	pub fn new(url: impl Into<String>) -> Self {
+		Self {
+			url: url.into(),
+			description: None,
+			spoiler: false,
+		}
+	}
+
+	pub fn description(mut self, description: impl Into<String>) -> Self {
+		self.description = Some(description.into());
+		self
+	}
+
+	pub fn spoiler(mut self, spoiler: bool) -> Self {
+		self.spoiler = spoiler;
+		self
+	}
+
+	fn to_json(&self) -> Value {
+		let mut json = json!({
+			"media": { "url": self.url },
+			"spoiler": self.spoiler,
+		});
+
+		if let Some(description) = &self.description {
+			json["description"] = json!(description);
+		}
+
+		json
+	}
+}
+
+/// One to three texts shown next to a thumbnail.
+#[derive(Clone, Debug, Default)]
+pub struct Section {
+	texts: Vec<TextDisplay>,
+	thumbnail: Option<Media>,
+}
+
+impl Section {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn text(mut self, content: impl Into<String>) -> Self {
+		self.texts.push(TextDisplay::new(content));
+		self
+	}
+
+	pub fn thumbnail(mut self, thumbnail: Media) -> Self {
+		self.thumbnail = Some(thumbnail);
+		self
+	}
+
+	fn to_json(&self) -> Value {
+		let accessory = match &self.thumbnail {
+			Some(thumbnail) => {
+				let mut json = thumbnail.to_json();
+				json["type"] = json!(11);
+				json
+			}
+			None => Value::Null,
+		};
+
+		json!({
+			"type": 9,
+			"components": self.texts.iter().map(TextDisplay::to_json).collect::<Vec<_>>(),
+			"accessory": accessory,
+		})
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MediaGallery {
+	items: Vec<Media>,
+}
+
+impl MediaGallery {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn item(mut self, item: Media) -> Self {
+		self.items.push(item);
+		self
+	}
+
+	fn to_json(&self) -> Value {
+		json!({
+			"type": 12,
+			"items": self.items.iter().map(Media::to_json).collect::<Vec<_>>(),
+		})
+	}
+}
+
+/// An uploaded file, referred to as `attachment://<filename>`.
+#[derive(Clone, Debug)]
+pub struct FileDisplay {
+	url: String,
+	spoiler: bool,
+}
+
+impl FileDisplay {
+	pub fn new(filename: &str) -> Self {
+		Self {
+			url: format!("attachment://{filename}"),
+			spoiler: false,
+		}
+	}
+
+	pub fn spoiler(mut self, spoiler: bool) -> Self {
+		self.spoiler = spoiler;
+		self
+	}
+
+	fn to_json(&self) -> Value {
+		json!({
+			"type": 13,
+			"file": { "url": self.url },
+			"spoiler": self.spoiler,
+		})
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Spacing {
+	#[default]
+	Small,
+	Large,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Separator {
+	divider: bool,
+	spacing: Spacing,
+}
+
+impl Separator {
+	pub fn new() -> Self {
+		Self {
+			divider: true,
+			spacing: Spacing::Small,
+		}
+	}
+
+	pub fn divider(mut self, divider: bool) -> Self {
+		self.divider = divider;
+		self
+	}
+
+	pub fn spacing(mut self, spacing: Spacing) -> Self {
+		self.spacing = spacing;
+		self
+	}
+
+	fn to_json(self) -> Value {
+		let spacing = match self.spacing {
+			Spacing::Small => 1,
+			Spacing::Large => 2,
+		};
+
+		json!({
+			"type": 14,
+			"divider": self.divider,
+			"spacing": spacing,
+		})
+	}
+}
+
+impl Default for Separator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[derive(Clone, Debug)]
+enum Component {
+	TextDisplay(TextDisplay),
+	Section(Section),
+	MediaGallery(MediaGallery),
+	File(FileDisplay),
+	Separator(Separator),
+	Container(Container),
+}
+
+impl Component {
+	fn to_json(&self) -> Value {
+		match self {
+			Self::TextDisplay(text) => text.to_json(),
+			Self::Section(section) => section.to_json(),
+			Self::MediaGallery(gallery) => gallery.to_json(),
+			Self::File(file) => file.to_json(),
+			Self::Separator(separator) => separator.to_json(),
+			Self::Container(container) => container.to_json(),
+		}
+	}
+}
+
+/// Groups components in a box with an optional accent color.
+#[derive(Clone, Debug, Default)]
+pub struct Container {
+	components: Vec<Component>,
+	accent_color: Option<Colour>,
+	spoiler: bool,
+}
+
+impl Container {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn accent_color(mut self, accent_color: impl Into<Colour>) -> Self {
+		self.accent_color = Some(accent_color.into());
+		self
+	}
+
+	pub fn spoiler(mut self, spoiler: bool) -> Self {
+		self.spoiler = spoiler;
+		self
+	}
+
+	fn to_json(&self) -> Value {
+		let mut json = json!({
+			"type": 17,
+			"components": components_to_json(&self.components),
+			"spoiler": self.spoiler,
+		});
+
+		if let Some(accent_color) = self.accent_color {
+			json["accent_color"] = json!(accent_color.0);
+		}
+
+		json
+	}
+}
+
+/// The components of a message using the layout components, which replace its content and
+/// embeds.
+#[derive(Clone, Debug, Default)]
+pub struct ComponentsV2 {
+	components: Vec<Component>,
+	ephemeral: bool,
+}
+
+impl ComponentsV2 {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+		self.ephemeral = ephemeral;
+		self
+	}
+
+	pub fn container(self, container: Container) -> Self {
+		self.push(Component::Container(container))
+	}
+
+	pub fn flags(&self) -> u64 {
+		match self.ephemeral {
+			true => IS_COMPONENTS_V2 | EPHEMERAL,
+			false => IS_COMPONENTS_V2,
+		}
+	}
+
+	/// The message payload. Fails if a limit on the components is exceeded.
+	pub fn build(self) -> Result<Value> {
+		self.validate()?;
+
+		Ok(json!({
+			"flags": self.flags(),
+			"components": components_to_json(&self.components),
+		}))
+	}
+
+	fn validate(&self) -> Result<()> {
+		if self.components.is_empty() {
+			return Err(Error::LayoutLimit(LayoutLimit::Empty));
+		}
+
+		let mut count = 0;
+		let mut text_length = 0;
+
+		for component in &self.components {
+			validate_component(component, &mut count, &mut text_length)?;
+		}
+
+		if count > MAX_COMPONENTS {
+			return Err(Error::LayoutLimit(LayoutLimit::Components));
+		}
+
+		if text_length > MAX_TEXT_LENGTH {
+			return Err(Error::LayoutLimit(LayoutLimit::TextLength));
+		}
+
+		Ok(())
+	}
+}
+
+macro_rules! impl_component_list {
+	($t:ty) => {
+		impl $t {
+			fn push(mut self, component: Component) -> Self {
+				self.components.push(component);
+				self
+			}
+
+			pub fn text(self, content: impl Into<String>) -> Self {
+				self.push(Component::TextDisplay(TextDisplay::new(content)))
+			}
+
+			pub fn section(self, section: Section) -> Self {
+				self.push(Component::Section(section))
+			}
+
+			pub fn gallery(self, gallery: MediaGallery) -> Self {
+				self.push(Component::MediaGallery(gallery))
+			}
+
+			pub fn file(self, file: FileDisplay) -> Self {
+				self.push(Component::File(file))
+			}
+
+			pub fn separator(self, separator: Separator) -> Self {
+				self.push(Component::Separator(separator))
+			}
+		}
+	};
+}
+
+impl_component_list!(Container);
+impl_component_list!(ComponentsV2);
+
+fn validate_component(
+	component: &Component,
+	count: &mut usize,
+	text_length: &mut usize,
+) -> Result<()> {
+	*count += 1;
+
+	match component {
+		Component::TextDisplay(text) => {
+			*text_length += text.content.chars().count();
+		}
+		Component::Section(section) => {
+			if section.texts.is_empty() || section.texts.len() > MAX_SECTION_TEXTS {
+				return Err(Error::LayoutLimit(LayoutLimit::SectionTexts));
+			}
+
+			if section.thumbnail.is_none() {
+				return Err(Error::LayoutLimit(LayoutLimit::SectionAccessory));
+			}
+
+			*count += section.texts.len() + 1;
+			*text_length += section
+				.texts
+				.iter()
+				.map(|text| text.content.chars().count())
+				.sum::<usize>();
+		}
+		Component::MediaGallery(gallery) => {
+			if gallery.items.is_empty() || gallery.items.len() > MAX_GALLERY_ITEMS {
+				return Err(Error::LayoutLimit(LayoutLimit::GalleryItems));
+			}
+		}
+		Component::File(_) | Component::Separator(_) => {}
+		// Only `ComponentsV2` can hold a container, so they can't be nested.
+		Component::Container(container) => {
+			if container.components.is_empty() {
+				return Err(Error::LayoutLimit(LayoutLimit::Empty));
+			}
+
+			for component in &container.components {
+				validate_component(component, count, text_length)?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn components_to_json(components: &[Component]) -> Vec<Value> {
+	components.iter().map(Component::to_json).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn limit(components: ComponentsV2) -> LayoutLimit {
+		match components.build() {
+			Err(Error::LayoutLimit(limit)) => limit,
+			other => panic!("expected a layout limit, got {other:?}"),
+		}
+	}
+
+	fn section(texts: usize) -> Section {
+		(0..texts)
+			.fold(Section::new(), |section, n| section.text(n.to_string()))
+			.thumbnail(Media::new("https://example.com/thumbnail.png"))
+	}
+
+	fn gallery(items: usize) -> MediaGallery {
+		(0..items).fold(MediaGallery::new(), |gallery, n| {
+			gallery.item(Media::new(format!("https://example.com/{n}.png")))
+		})
+	}
+
+	#[test]
+	fn valid_layouts_build() {
+		let payload = ComponentsV2::new()
+			.ephemeral(true)
+			.text("title")
+			.container(
+				Container::new()
+					.section(section(MAX_SECTION_TEXTS))
+					.separator(Separator::new())
+					.gallery(gallery(MAX_GALLERY_ITEMS))
+					.file(FileDisplay::new("report.txt")),
+			)
+			.build()
+			.unwrap();
+
+		assert_eq!(payload["flags"], IS_COMPONENTS_V2 | EPHEMERAL);
+		assert_eq!(
+			payload["components"][1]["components"][0]["accessory"]["type"],
+			11
+		);
+	}
+
+	#[test]
+	fn messages_and_containers_must_not_be_empty() {
+		assert_eq!(limit(ComponentsV2::new()), LayoutLimit::Empty);
+		assert_eq!(
+			limit(ComponentsV2::new().container(Container::new())),
+			LayoutLimit::Empty
+		);
+	}
+
+	#[test]
+	fn sections_have_one_to_three_texts() {
+		for texts in [0, MAX_SECTION_TEXTS + 1] {
+			let components = ComponentsV2::new().section(section(texts));
+			assert_eq!(limit(components), LayoutLimit::SectionTexts, "{texts}");
+		}
+	}
+
+	#[test]
+	fn sections_need_a_thumbnail() {
+		let components = ComponentsV2::new().section(Section::new().text("text"));
+		assert_eq!(limit(components), LayoutLimit::SectionAccessory);
+	}
+
+	#[test]
+	fn galleries_have_one_to_ten_items() {
+		for items in [0, MAX_GALLERY_ITEMS + 1] {
+			let components = ComponentsV2::new().gallery(gallery(items));
+			assert_eq!(limit(components), LayoutLimit::GalleryItems, "{items}");
+		}
+	}
+
+	#[test]
+	fn nested_components_are_counted() {
+		// The container itself counts, as do a section's texts and thumbnail.
+		let container = |texts: usize| {
+			(0..texts).fold(Container::new().section(section(1)), |container, n| {
+				container.text(n.to_string())
+			})
+		};
+
+		let fits = ComponentsV2::new().container(container(MAX_COMPONENTS - 4));
+		assert!(fits.build().is_ok());
+
+		let too_many = ComponentsV2::new().container(container(MAX_COMPONENTS - 3));
+		assert_eq!(limit(too_many), LayoutLimit::Components);
+	}
+
+	#[test]
+	fn text_length_counts_characters_of_all_texts() {
+		let text = |len: usize| "é".repeat(len);
+		let components = |len: usize| {
+			ComponentsV2::new()
+				.text(text(MAX_TEXT_LENGTH / 2))
+				.container(
+					Container::new().section(
+						Section::new()
+							.text(text(len - MAX_TEXT_LENGTH / 2))
+							.thumbnail(Media::new("https://example.com/thumbnail.png")),
+					),
+				)
+		};
+
+		assert!(components(MAX_TEXT_LENGTH).build().is_ok());
+		assert_eq!(
+			limit(components(MAX_TEXT_LENGTH + 1)),
+			LayoutLimit::TextLength
+		);
+	}
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use serenity::all::{
+	Context, CreateCommand, Interaction, Message, MessageId, PartialMember, ResolvedTarget, User,
+	UserId,
+};
+
+use crate::error::{Error, Result};
+
+pub use serein_macros::{MessageCommand, UserCommand};
+
+#[async_trait]
+pub trait MessageCommand<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
+	fn create(name: impl Into<String>) -> CreateCommand;
+}
+
+#[async_trait]
+pub trait UserCommand<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
+	fn create(name: impl Into<String>) -> CreateCommand;
+}
+
+pub trait CommandTarget: Sized {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self>;
+}
+
+impl CommandTarget for Message {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::Message(message) => Ok(message.to_owned()),
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
+
+impl CommandTarget for MessageId {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::Message(message) => Ok(message.id),
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
+
+impl CommandTarget for User {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::User(user, _) => Ok(user.to_owned()),
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
+
+impl CommandTarget for UserId {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::User(user, _) => Ok(user.id),
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
+
+impl CommandTarget for PartialMember {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::User(_, partial_member) => match partial_member {
+				Some(partial_member) => Ok(partial_member.to_owned()),
+				None => Err(Error::BadOptionValue),
+			},
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
+
+impl CommandTarget for Option<PartialMember> {
+	fn try_from_resolved_target(target: ResolvedTarget) -> Result<Self> {
+		match target {
+			ResolvedTarget::User(_, partial_member) => Ok(partial_member.cloned()),
+			_ => Err(Error::BadOptionType),
+		}
+	}
+}
@@ -0,0 +1,251 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use serenity::all::{
+	AuthorizingIntegrationOwner, AutocompleteChoice, CommandInteraction, Context,
+	CreateAutocompleteResponse, CreateInteractionResponse, CreateInteractionResponseFollowup,
+	CreateInteractionResponseMessage, EditInteractionResponse, Http, InteractionContext,
+	LightMethod, Message, ModalInteraction, ModalInteractionCollector,
+};
+use serenity::http::{Request, Route};
+
+use crate::collector::{ComponentCollector, FromComponent};
+use crate::components_v2::ComponentsV2;
+use crate::error::{Error, Result};
+use crate::modal::Modal;
+use crate::router::RESERVED_PREFIX;
+
+pub struct Ctx {
+	ctx: Context,
+	int: CommandInteraction,
+	acknowledged: AtomicBool,
+}
+
+impl Ctx {
+	pub fn new(ctx: Context, int: CommandInteraction) -> Self {
+		Self {
+			ctx,
+			int,
+			acknowledged: AtomicBool::new(false),
+		}
+	}
+
+	pub fn context(&self) -> &Context {
+		&self.ctx
+	}
+
+	pub fn interaction(&self) -> &CommandInteraction {
+		&self.int
+	}
+
+	pub fn http(&self) -> &Http {
+		&self.ctx.http
+	}
+
+	/// Where the command was used: in a guild, in the bot's DMs or in another private channel.
+	pub fn invocation_context(&self) -> Option<InteractionContext> {
+		self.int.context
+	}
+
+	/// Whether the app is installed to the invoking user, as opposed to only the guild.
+	pub fn is_user_installed(&self) -> bool {
+		self.int
+			.authorizing_integration_owners
+			.0
+			.iter()
+			.any(|owner| matches!(owner, AuthorizingIntegrationOwner::UserInstall(_)))
+	}
+
+	pub fn is_acknowledged(&self) -> bool {
+		self.acknowledged.load(Ordering::Acquire)
+	}
+
+	pub async fn respond(&self, response: CreateInteractionResponse) -> Result<()> {
+		self.acknowledge(self.int.create_response(&self.ctx, response))
+			.await
+	}
+
+	async fn acknowledge(
+		&self,
+		response: impl Future<Output = serenity::Result<()>>,
+	) -> Result<()> {
+		if self
+			.acknowledged
+			.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+			.is_err()
+		{
+			return Err(Error::AlreadyAcknowledged);
+		}
+
+		match response.await {
+			Ok(()) => Ok(()),
+			Err(err) => {
+				self.acknowledged.store(false, Ordering::Release);
+				Err(Box::new(err).into())
+			}
+		}
+	}
+
+	pub async fn reply(&self, content: impl Into<String>) -> Result<()> {
+		let message = CreateInteractionResponseMessage::new().content(content);
+		self.respond(CreateInteractionResponse::Message(message))
+			.await
+	}
+
+	pub async fn reply_ephemeral(&self, content: impl Into<String>) -> Result<()> {
+		let message = CreateInteractionResponseMessage::new()
+			.content(content)
+			.ephemeral(true);
+		self.respond(CreateInteractionResponse::Message(message))
+			.await
+	}
+
+	pub async fn defer(&self) -> Result<()> {
+		let message = CreateInteractionResponseMessage::new();
+		self.respond(CreateInteractionResponse::Defer(message))
+			.await
+	}
+
+	pub async fn defer_ephemeral(&self) -> Result<()> {
+		let message = CreateInteractionResponseMessage::new().ephemeral(true);
+		self.respond(CreateInteractionResponse::Defer(message))
+			.await
+	}
+
+	pub async fn autocomplete(&self, mut choices: Vec<AutocompleteChoice>) -> Result<()> {
+		choices.truncate(25);
+		let response = CreateAutocompleteResponse::new().set_choices(choices);
+		self.respond(CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+
+	/// Responds with the modal and waits for the invoking user to submit it. Returns `None` if the
+	/// modal isn't submitted before the timeout. A submission that doesn't parse is acknowledged
+	/// before the error is returned.
+	pub async fn modal<M: Modal>(
+		&self,
+		timeout: Duration,
+	) -> Result<Option<(M, ModalInteraction)>> {
+		let custom_id = format!("{RESERVED_PREFIX}:modal:{}", self.int.id);
+
+		self.respond(CreateInteractionResponse::Modal(M::create(
+			custom_id.clone(),
+		)))
+		.await?;
+
+		let collected = ModalInteractionCollector::new(&self.ctx)
+			.author_id(self.int.user.id)
+			.custom_ids(vec![custom_id])
+			.timeout(timeout)
+			.await;
+
+		let Some(int) = collected else {
+			return Ok(None);
+		};
+
+		match M::try_from_modal_interaction(&int) {
+			Ok(modal) => Ok(Some((modal, int))),
+			Err(err) => {
+				// Closes the modal, so the user isn't shown that the interaction failed.
+				int.create_response(&self.ctx, CreateInteractionResponse::Acknowledge)
+					.await
+					.map_err(Box::new)?;
+
+				Err(err)
+			}
+		}
+	}
+
+	/// Collects the components on `message` that are clicked by the invoking user. `message` has to
+	/// be the response to this command or one of its followups, since the components are disabled
+	/// through the interaction.
+	pub fn collect_components<P: FromComponent + Send + 'static>(
+		&self,
+		message: &Message,
+	) -> ComponentCollector<P> {
+		ComponentCollector::new(&self.ctx, message)
+			.author_id(self.int.user.id)
+			.interaction_token(&self.int.token)
+	}
+
+	pub async fn edit_response(&self, edit: EditInteractionResponse) -> Result<Message> {
+		let message = self
+			.int
+			.edit_response(&self.ctx.http, edit)
+			.await
+			.map_err(Box::new)?;
+
+		Ok(message)
+	}
+
+	pub async fn followup(&self, followup: CreateInteractionResponseFollowup) -> Result<Message> {
+		let message = self
+			.int
+			.create_followup(&self.ctx.http, followup)
+			.await
+			.map_err(Box::new)?;
+
+		Ok(message)
+	}
+
+	pub async fn respond_components_v2(&self, message: ComponentsV2) -> Result<()> {
+		let response = json!({
+			"type": 4,
+			"data": message.build()?,
+		});
+
+		self.acknowledge(self.ctx.http.create_interaction_response(
+			self.int.id,
+			&self.int.token,
+			&response,
+			vec![],
+		))
+		.await
+	}
+
+	// Serenity can't deserialize the returned message, so the requests below are sent raw.
+
+	pub async fn edit_response_components_v2(&self, message: ComponentsV2) -> Result<()> {
+		let route = Route::WebhookOriginalInteractionResponse {
+			application_id: self.int.application_id,
+			token: &self.int.token,
+		};
+
+		self.send_raw(Request::new(route, LightMethod::Patch), message)
+			.await
+	}
+
+	pub async fn followup_components_v2(&self, message: ComponentsV2) -> Result<()> {
+		let route = Route::WebhookFollowupMessages {
+			application_id: self.int.application_id,
+			token: &self.int.token,
+		};
+
+		self.send_raw(Request::new(route, LightMethod::Post), message)
+			.await
+	}
+
+	async fn send_raw(&self, request: Request<'_>, message: ComponentsV2) -> Result<()> {
+		let body = serde_json::to_vec(&message.build()?)
+			.map_err(|err| Box::new(serenity::Error::Json(err)))?;
+
+		self.ctx
+			.http
+			.fire::<Value>(request.body(Some(body)))
+			.await
+			.map_err(Box::new)?;
+
+		Ok(())
+	}
+
+	pub async fn delete_response(&self) -> Result<()> {
+		self.int
+			.delete_response(&self.ctx.http)
+			.await
+			.map_err(Box::new)?;
+
+		Ok(())
+	}
+}
@@ -12,6 +12,28 @@ pub enum Error {
 	BadOptionType,
 	#[error("bad option value")]
 	BadOptionValue,
+	#[error("missing field {0}")]
+	MissingField(String),
+	#[error("field {0} too long")]
+	FieldTooLong(String),
+	#[error("bad custom id")]
+	BadCustomId,
+	#[error("custom id too long")]
+	CustomIdTooLong,
+	#[error("bad custom id signature")]
+	BadSignature,
+	#[error("no custom id signing key set")]
+	NoSigningKey,
+	#[error("bad component type")]
+	BadComponentType,
+	#[error("bad component value")]
+	BadComponentValue,
+	#[error("component layout: {0}")]
+	LayoutLimit(#[from] crate::layout::LayoutLimit),
+	#[error("paginator has no pages")]
+	NoPages,
+	#[error("interaction already acknowledged")]
+	AlreadyAcknowledged,
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use async_trait::async_trait;
+use serenity::all::{Context, EventHandler, GuildId, Interaction, Ready};
+use serenity::futures::future::BoxFuture;
+
+use crate::component::{ComponentHandler, PersistentComponent};
+use crate::error::Error;
+use crate::modal::ModalHandler;
+use crate::router::InteractionRouter;
+use crate::signing::SigningKey;
+use crate::slash::CommandTree;
+
+type ErrorCallback = Box<dyn Fn(Context, Error) -> BoxFuture<'static, ()> + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Registration {
+	None,
+	Global,
+	Guilds(Vec<GuildId>),
+}
+
+pub struct SereinHandler<T, D = ()> {
+	data: D,
+	registration: Registration,
+	on_error: ErrorCallback,
+	registered: AtomicBool,
+	router: InteractionRouter<T, D>,
+}
+
+impl<T: CommandTree> SereinHandler<T> {
+	pub fn new() -> Self {
+		Self::with_data(())
+	}
+}
+
+impl<T: CommandTree<D>, D: Send + Sync> SereinHandler<T, D> {
+	pub fn with_data(data: D) -> Self {
+		Self {
+			data,
+			registration: Registration::Global,
+			on_error: Box::new(|_ctx, err| {
+				Box::pin(async move {
+					tracing::error!(error = %err, "serein interaction error");
+				})
+			}),
+			registered: AtomicBool::new(false),
+			router: InteractionRouter::new(),
+		}
+	}
+
+	pub fn registration(mut self, registration: Registration) -> Self {
+		self.registration = registration;
+		self
+	}
+
+	pub fn register_in_guilds(self, guild_ids: impl IntoIterator<Item = GuildId>) -> Self {
+		self.registration(Registration::Guilds(guild_ids.into_iter().collect()))
+	}
+
+	pub fn on_error<F, Fut>(mut self, on_error: F) -> Self
+	where
+		F: Fn(Context, Error) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = ()> + Send + 'static,
+	{
+		self.on_error = Box::new(move |ctx, err| Box::pin(on_error(ctx, err)));
+		self
+	}
+
+	/// Verifies the custom ids of signed persistent components with this key.
+	pub fn signing_key(mut self, key: SigningKey) -> Self {
+		self.router = self.router.signing_key(key);
+		self
+	}
+
+	pub fn router(mut self, router: InteractionRouter<T, D>) -> Self {
+		self.router = router;
+		self
+	}
+
+	pub fn component(
+		mut self,
+		prefix: impl Into<String>,
+		handler: impl ComponentHandler<D> + 'static,
+	) -> Self {
+		self.router = self.router.component(prefix, handler);
+		self
+	}
+
+	pub fn persistent<C: PersistentComponent<D> + 'static>(mut self) -> Self {
+		self.router = self.router.persistent::<C>();
+		self
+	}
+
+	pub fn modal(
+		mut self,
+		prefix: impl Into<String>,
+		handler: impl ModalHandler<D> + 'static,
+	) -> Self {
+		self.router = self.router.modal(prefix, handler);
+		self
+	}
+}
+
+impl<T: CommandTree> Default for SereinHandler<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait]
+impl<T, D> EventHandler for SereinHandler<T, D>
+where
+	T: CommandTree<D> + Send + Sync + 'static,
+	D: Send + Sync + 'static,
+{
+	// Every shard fires `ready` on each new session, but the commands only need syncing once. The
+	// flag is cleared again if syncing fails, so that the next `ready` retries.
+	async fn ready(&self, ctx: Context, _ready: Ready) {
+		if self.registered.swap(true, Ordering::AcqRel) {
+			return;
+		}
+
+		let mut errors = Vec::new();
+
+		match &self.registration {
+			Registration::None => {}
+			Registration::Global => {
+				if let Err(err) = T::sync_global(&ctx.http).await {
+					errors.push(err);
+				}
+			}
+			Registration::Guilds(guild_ids) => {
+				for &guild_id in guild_ids {
+					if let Err(err) = T::sync_in_guild(&ctx.http, guild_id).await {
+						errors.push(err);
+					}
+				}
+			}
+		}
+
+		if !errors.is_empty() {
+			self.registered.store(false, Ordering::Release);
+		}
+
+		for err in errors {
+			(self.on_error)(ctx.clone(), err).await;
+		}
+	}
+
+	async fn interaction_create(&self, ctx: Context, int: Interaction) {
+		if let Err(err) = self.router.dispatch(ctx.clone(), int, &self.data).await {
+			(self.on_error)(ctx, err).await;
+		}
+	}
+}
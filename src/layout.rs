@@ -0,0 +1,299 @@
+use serenity::all::{CreateActionRow, CreateButton, CreateSelectMenu};
+use thiserror::Error;
+
+use crate::component::Button;
+use crate::components_v2::{MAX_COMPONENTS, MAX_GALLERY_ITEMS, MAX_SECTION_TEXTS, MAX_TEXT_LENGTH};
+use crate::error::{Error, Result};
+use crate::signing::SigningKey;
+
+pub const MAX_ROWS: usize = 5;
+pub const MAX_BUTTONS_PER_ROW: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum LayoutLimit {
+	#[error("a message can have at most {MAX_ROWS} action rows")]
+	Rows,
+	#[error("an action row can have at most {MAX_BUTTONS_PER_ROW} buttons")]
+	ButtonsPerRow,
+	#[error("a select menu must be alone in its action row")]
+	SelectMenuNotAlone,
+	#[error("an action row can't be empty")]
+	EmptyRow,
+	#[error("a message or container can't be empty")]
+	Empty,
+	#[error("a message can have at most {MAX_COMPONENTS} components, counting nested ones")]
+	Components,
+	#[error("the texts of a message can have at most {MAX_TEXT_LENGTH} characters in total")]
+	TextLength,
+	#[error("a section must have 1 to {MAX_SECTION_TEXTS} texts")]
+	SectionTexts,
+	#[error("a section must have a thumbnail accessory")]
+	SectionAccessory,
+	#[error("a media gallery must have 1 to {MAX_GALLERY_ITEMS} items")]
+	GalleryItems,
+}
+
+#[derive(Clone, Debug)]
+pub enum LayoutComponent {
+	Button(CreateButton),
+	SelectMenu(CreateSelectMenu),
+}
+
+impl From<CreateButton> for LayoutComponent {
+	fn from(button: CreateButton) -> Self {
+		Self::Button(button)
+	}
+}
+
+impl From<CreateSelectMenu> for LayoutComponent {
+	fn from(menu: CreateSelectMenu) -> Self {
+		Self::SelectMenu(menu)
+	}
+}
+
+/// Lays out the components of a message. Buttons are packed into rows as they're added and
+/// select menus each get a row of their own. Discord's limits are checked in [`build`](Self::build),
+/// before anything is sent.
+#[derive(Debug, Default)]
+pub struct Layout {
+	rows: Vec<Vec<LayoutComponent>>,
+	// Set by `break_row` and explicit rows, so the next button doesn't join the last row.
+	row_closed: bool,
+	signing_key: Option<SigningKey>,
+	error: Option<Error>,
+}
+
+impl Layout {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Signs the custom ids of the signed buttons added after this.
+	pub fn signing_key(mut self, key: SigningKey) -> Self {
+		self.signing_key = Some(key);
+		self
+	}
+
+	pub fn button(self, button: &impl Button) -> Self {
+		match button.create(self.signing_key.as_ref()) {
+			Ok(button) => self.create_button(button),
+			Err(err) => self.fail(err),
+		}
+	}
+
+	pub fn create_button(mut self, button: CreateButton) -> Self {
+		let packable = !self.row_closed
+			&& self.rows.last().is_some_and(|row| {
+				row.len() < MAX_BUTTONS_PER_ROW
+					&& row
+						.iter()
+						.all(|component| matches!(component, LayoutComponent::Button(_)))
+			});
+
+		match self.rows.last_mut() {
+			Some(row) if packable => row.push(LayoutComponent::Button(button)),
+			_ => self.rows.push(vec![LayoutComponent::Button(button)]),
+		}
+
+		self.row_closed = false;
+		self
+	}
+
+	pub fn select_menu(mut self, menu: CreateSelectMenu) -> Self {
+		self.rows.push(vec![LayoutComponent::SelectMenu(menu)]);
+		self.row_closed = true;
+		self
+	}
+
+	/// Starts a new row for the buttons added after this.
+	pub fn break_row(mut self) -> Self {
+		self.row_closed = true;
+		self
+	}
+
+	/// Adds a row with exactly these components.
+	pub fn row<C: Into<LayoutComponent>>(
+		mut self,
+		components: impl IntoIterator<Item = C>,
+	) -> Self {
+		self.rows
+			.push(components.into_iter().map(Into::into).collect());
+		self.row_closed = true;
+		self
+	}
+
+	pub fn build(self) -> Result<Vec<CreateActionRow>> {
+		if let Some(err) = self.error {
+			return Err(err);
+		}
+
+		if self.rows.len() > MAX_ROWS {
+			return Err(Error::LayoutLimit(LayoutLimit::Rows));
+		}
+
+		self.rows.into_iter().map(build_row).collect()
+	}
+
+	fn fail(mut self, err: Error) -> Self {
+		self.error.get_or_insert(err);
+		self
+	}
+}
+
+fn build_row(row: Vec<LayoutComponent>) -> Result<CreateActionRow> {
+	let mut buttons = Vec::with_capacity(row.len());
+	let mut menu = None;
+
+	for component in row {
+		match component {
+			LayoutComponent::Button(button) => buttons.push(button),
+			LayoutComponent::SelectMenu(select_menu) => {
+				if menu.replace(select_menu).is_some() {
+					return Err(Error::LayoutLimit(LayoutLimit::SelectMenuNotAlone));
+				}
+			}
+		}
+	}
+
+	match menu {
+		Some(_) if !buttons.is_empty() => Err(Error::LayoutLimit(LayoutLimit::SelectMenuNotAlone)),
+		Some(menu) => Ok(CreateActionRow::SelectMenu(menu)),
+		None if buttons.is_empty() => Err(Error::LayoutLimit(LayoutLimit::EmptyRow)),
+		None if buttons.len() > MAX_BUTTONS_PER_ROW => {
+			Err(Error::LayoutLimit(LayoutLimit::ButtonsPerRow))
+		}
+		None => Ok(CreateActionRow::Buttons(buttons)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serenity::all::CreateSelectMenuKind;
+
+	use super::*;
+
+	#[derive(Button)]
+	#[serein(signed)]
+	struct Grant {
+		role: u64,
+	}
+
+	fn button(n: usize) -> CreateButton {
+		CreateButton::new(format!("button:{n}"))
+	}
+
+	fn menu() -> CreateSelectMenu {
+		CreateSelectMenu::new("menu", CreateSelectMenuKind::String { options: vec![] })
+	}
+
+	/// The number of buttons in each row, or `None` for a select menu.
+	fn shape(rows: &[CreateActionRow]) -> Vec<Option<usize>> {
+		rows.iter()
+			.map(|row| match row {
+				CreateActionRow::Buttons(buttons) => Some(buttons.len()),
+				_ => None,
+			})
+			.collect()
+	}
+
+	fn limit(layout: Layout) -> LayoutLimit {
+		match layout.build() {
+			Err(Error::LayoutLimit(limit)) => limit,
+			other => panic!("expected a layout limit, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn buttons_are_packed_across_rows() {
+		let rows = (0..MAX_BUTTONS_PER_ROW + 2)
+			.fold(Layout::new(), |layout, n| layout.create_button(button(n)))
+			.build()
+			.unwrap();
+
+		assert_eq!(shape(&rows), [Some(MAX_BUTTONS_PER_ROW), Some(2)]);
+	}
+
+	#[test]
+	fn break_row_starts_a_new_row() {
+		let rows = Layout::new()
+			.create_button(button(0))
+			.break_row()
+			.create_button(button(1))
+			.create_button(button(2))
+			.build()
+			.unwrap();
+
+		assert_eq!(shape(&rows), [Some(1), Some(2)]);
+	}
+
+	#[test]
+	fn select_menus_get_their_own_row() {
+		let rows = Layout::new()
+			.create_button(button(0))
+			.select_menu(menu())
+			.create_button(button(1))
+			.build()
+			.unwrap();
+
+		assert_eq!(shape(&rows), [Some(1), None, Some(1)]);
+	}
+
+	#[test]
+	fn explicit_rows_are_kept_as_is() {
+		let rows = Layout::new()
+			.row([button(0), button(1)])
+			.create_button(button(2))
+			.build()
+			.unwrap();
+
+		assert_eq!(shape(&rows), [Some(2), Some(1)]);
+	}
+
+	#[test]
+	fn too_many_rows() {
+		let layout = (0..=MAX_ROWS).fold(Layout::new(), |layout, _| layout.select_menu(menu()));
+		assert_eq!(limit(layout), LayoutLimit::Rows);
+	}
+
+	#[test]
+	fn too_many_buttons_in_a_row() {
+		let layout = Layout::new().row((0..=MAX_BUTTONS_PER_ROW).map(button));
+		assert_eq!(limit(layout), LayoutLimit::ButtonsPerRow);
+	}
+
+	#[test]
+	fn select_menus_must_be_alone() {
+		let with_button = Layout::new().row([
+			LayoutComponent::from(button(0)),
+			LayoutComponent::from(menu()),
+		]);
+		assert_eq!(limit(with_button), LayoutLimit::SelectMenuNotAlone);
+
+		let with_menu = Layout::new().row([menu(), menu()]);
+		assert_eq!(limit(with_menu), LayoutLimit::SelectMenuNotAlone);
+	}
+
+	#[test]
+	fn rows_must_not_be_empty() {
+		let layout = Layout::new().row(Vec::<CreateButton>::new());
+		assert_eq!(limit(layout), LayoutLimit::EmptyRow);
+	}
+
+	#[test]
+	fn failed_buttons_fail_the_build() {
+		let layout = Layout::new()
+			.button(&Grant { role: 5 })
+			.create_button(button(0))
+			.row((0..=MAX_BUTTONS_PER_ROW).map(button));
+
+		assert!(matches!(layout.build(), Err(Error::NoSigningKey)));
+
+		let rows = Layout::new()
+			.signing_key(SigningKey::new("secret"))
+			.button(&Grant { role: 5 })
+			.build()
+			.unwrap();
+
+		assert_eq!(shape(&rows), [Some(1)]);
+	}
+}
@@ -1,9 +1,24 @@
 extern crate self as serein;
 
+pub mod collector;
+pub mod component;
+pub mod components_v2;
+pub mod context_menu;
+pub mod ctx;
 pub mod error;
+pub mod handler;
+pub mod layout;
+pub mod modal;
 pub mod options;
+pub mod router;
+pub mod signing;
 pub mod slash;
+pub mod sync;
+pub mod widget;
 
 pub use serein_macros as macros;
 
+pub use ctx::Ctx;
 pub use error::{Error, Result};
+pub use handler::SereinHandler;
+pub use router::InteractionRouter;
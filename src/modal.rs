@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use serenity::all::{Context, CreateModal, ModalInteraction};
+
+use crate::error::Result;
+
+pub use serein_macros::Modal;
+
+pub trait Modal: Sized {
+	fn create(custom_id: impl Into<String>) -> CreateModal;
+	fn try_from_modal_interaction(int: &ModalInteraction) -> Result<Self>;
+}
+
+/// Handles modal submissions whose custom_id starts with the prefix the handler is registered
+/// under in an [`InteractionRouter`](crate::router::InteractionRouter).
+#[async_trait]
+pub trait ModalHandler<D: Send + Sync = ()>: Send + Sync {
+	async fn handle(&self, ctx: Context, int: ModalInteraction, data: &D) -> Result<()>;
+}
+
+pub trait ModalField: Sized {
+	const REQUIRED: bool;
+
+	fn from_input(value: Option<String>) -> Option<Self>;
+}
+
+impl ModalField for String {
+	const REQUIRED: bool = true;
+
+	fn from_input(value: Option<String>) -> Option<Self> {
+		value
+	}
+}
+
+impl ModalField for Option<String> {
+	const REQUIRED: bool = false;
+
+	fn from_input(value: Option<String>) -> Option<Self> {
+		Some(value)
+	}
+}
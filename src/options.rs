@@ -1,6 +1,7 @@
 use serenity::all::{
-	Attachment, AttachmentId, ChannelId, CommandOptionType, CreateCommandOption, GenericId,
-	PartialChannel, PartialMember, ResolvedValue, Role, RoleId, Unresolved, User, UserId,
+	Attachment, AttachmentId, ChannelId, ChannelType, CommandOptionType, CreateCommandOption,
+	GenericId, PartialChannel, PartialMember, ResolvedValue, Role, RoleId, Unresolved, User,
+	UserId,
 };
 
 use crate::error::{Error, Result};
@@ -17,6 +18,32 @@ pub trait CommandOption: Sized {
 	fn create(name: impl Into<String>, desc: impl Into<String>) -> CreateCommandOption;
 }
 
+/// A channel option, which `#[serein(channel_types = [...])]` can restrict to some channel types.
+/// Not implemented for the typed channel ids like [`TextChannelId`], which are restricted already.
+#[diagnostic::on_unimplemented(
+	message = "`{Self}` can't be restricted to some channel types",
+	label = "`channel_types` can only be set on `ChannelId` and `PartialChannel` options",
+	note = "typed channel ids like `TextChannelId` already restrict the channel types"
+)]
+pub trait ChannelOption: CommandOption {}
+
+/// Used by the derives to set `channel_types`, so that it's rejected on options that aren't
+/// [`ChannelOption`]s.
+///
+/// ```compile_fail
+/// use serein::options::{TextChannelId, channel_types};
+/// use serenity::all::{ChannelType, CommandOptionType, CreateCommandOption};
+///
+/// let option = CreateCommandOption::new(CommandOptionType::Channel, "channel", "A channel");
+/// channel_types::<TextChannelId>(option, vec![ChannelType::Voice]);
+/// ```
+pub fn channel_types<T: ChannelOption>(
+	option: CreateCommandOption,
+	channel_types: Vec<ChannelType>,
+) -> CreateCommandOption {
+	option.channel_types(channel_types)
+}
+
 macro_rules! impl_create {
 	($kind:expr) => {
 		fn create(name: impl Into<String>, desc: impl Into<String>) -> CreateCommandOption {
@@ -127,6 +154,8 @@ impl CommandOption for PartialChannel {
 	impl_create!(CommandOptionType::Channel);
 }
 
+impl ChannelOption for PartialChannel {}
+
 impl CommandOption for Role {
 	fn try_from_resolved_value(value: ResolvedValue) -> Result<Self> {
 		match value {
@@ -203,6 +232,52 @@ impl CommandOption for ChannelId {
 	impl_create!(CommandOptionType::Channel);
 }
 
+impl ChannelOption for ChannelId {}
+
+// Channel ids restricted to some channel types. Discord only offers channels of those types, and
+// the type is checked again when parsing, so channels that weren't resolved are rejected.
+macro_rules! channel_id_wrapper {
+	($name:ident, [$($kind:ident),+]) => {
+		#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+		pub struct $name(pub ChannelId);
+
+		impl $name {
+			pub const CHANNEL_TYPES: &[ChannelType] = &[$(ChannelType::$kind),+];
+		}
+
+		impl From<$name> for ChannelId {
+			fn from(id: $name) -> Self {
+				id.0
+			}
+		}
+
+		impl CommandOption for $name {
+			fn try_from_resolved_value(value: ResolvedValue) -> Result<Self> {
+				match value {
+					ResolvedValue::Channel(channel) if Self::CHANNEL_TYPES.contains(&channel.kind) => {
+						Ok(Self(channel.id))
+					}
+					ResolvedValue::Channel(_) | ResolvedValue::Unresolved(Unresolved::Channel(_)) => {
+						Err(Error::BadOptionValue)
+					}
+					_ => Err(Error::BadOptionType),
+				}
+			}
+
+			fn create(name: impl Into<String>, desc: impl Into<String>) -> CreateCommandOption {
+				CreateCommandOption::new(CommandOptionType::Channel, name, desc)
+					.required(true)
+					.channel_types(Self::CHANNEL_TYPES.to_vec())
+			}
+		}
+	};
+}
+
+channel_id_wrapper!(TextChannelId, [Text, News]);
+channel_id_wrapper!(VoiceChannelId, [Voice, Stage]);
+channel_id_wrapper!(CategoryId, [Category]);
+channel_id_wrapper!(ForumChannelId, [Forum]);
+
 impl CommandOption for GenericId {
 	fn try_from_resolved_value(value: ResolvedValue) -> Result<Self> {
 		match value {
@@ -240,3 +315,51 @@ impl<T: CommandOption> CommandOption for Option<T> {
 		<T as CommandOption>::create(name, desc).required(false)
 	}
 }
+
+impl<T: ChannelOption> ChannelOption for Option<T> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn typed_channel_ids_reject_unresolved_channels() {
+		let unresolved = || ResolvedValue::Unresolved(Unresolved::Channel(ChannelId::new(1)));
+
+		assert_eq!(
+			ChannelId::try_from_resolved_value(unresolved()).unwrap(),
+			ChannelId::new(1)
+		);
+		assert!(matches!(
+			TextChannelId::try_from_resolved_value(unresolved()),
+			Err(Error::BadOptionValue)
+		));
+		assert!(matches!(
+			TextChannelId::try_from_resolved_value(ResolvedValue::Boolean(true)),
+			Err(Error::BadOptionType)
+		));
+	}
+
+	#[test]
+	fn typed_channel_ids_accept_their_channel_types() {
+		let channel = |kind: ChannelType| -> PartialChannel {
+			serde_json::from_value(serde_json::json!({ "id": "1", "type": u8::from(kind) }))
+				.unwrap()
+		};
+
+		for kind in [ChannelType::Text, ChannelType::News] {
+			assert_eq!(
+				TextChannelId::try_from_resolved_value(ResolvedValue::Channel(&channel(kind)))
+					.unwrap(),
+				TextChannelId(ChannelId::new(1))
+			);
+		}
+
+		assert!(matches!(
+			TextChannelId::try_from_resolved_value(ResolvedValue::Channel(&channel(
+				ChannelType::Voice
+			))),
+			Err(Error::BadOptionValue)
+		));
+	}
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use serenity::all::{Context, Interaction};
+
+use crate::component::{
+	CUSTOM_ID_SEPARATOR, ComponentHandler, PersistentComponent, PersistentHandler, PersistentRoute,
+};
+use crate::error::Result;
+use crate::modal::ModalHandler;
+use crate::signing::SigningKey;
+use crate::slash::CommandTree;
+
+/// Custom ids with this prefix belong to interactions that serein awaits itself, like the modals
+/// shown by [`Ctx::modal`](crate::Ctx::modal), so the router leaves them alone.
+pub const RESERVED_PREFIX: &str = "serein";
+
+/// Dispatches every kind of interaction: commands and autocomplete go to the command tree `T`,
+/// while components and modal submissions go to the handler registered for the first segment of
+/// their custom_id. Those without a registered handler are left alone, since they may be awaited
+/// by a collector instead.
+pub struct InteractionRouter<T, D = ()> {
+	components: HashMap<String, ComponentRoute<D>>,
+	modals: HashMap<String, Box<dyn ModalHandler<D>>>,
+	signing_key: Option<SigningKey>,
+	_tree: PhantomData<fn() -> T>,
+}
+
+enum ComponentRoute<D> {
+	Handler(Box<dyn ComponentHandler<D>>),
+	Persistent(Box<dyn PersistentRoute<D>>),
+}
+
+impl<T: CommandTree<D>, D: Send + Sync> InteractionRouter<T, D> {
+	pub fn new() -> Self {
+		Self {
+			components: HashMap::new(),
+			modals: HashMap::new(),
+			signing_key: None,
+			_tree: PhantomData,
+		}
+	}
+
+	/// # Panics
+	///
+	/// If `prefix` is [`RESERVED_PREFIX`].
+	pub fn component(
+		mut self,
+		prefix: impl Into<String>,
+		handler: impl ComponentHandler<D> + 'static,
+	) -> Self {
+		let prefix = checked_prefix(prefix.into());
+		self.components
+			.insert(prefix, ComponentRoute::Handler(Box::new(handler)));
+		self
+	}
+
+	/// Routes the interactions of `C`, by the prefix of its custom_id, to its
+	/// [`PersistentComponent`] handler.
+	///
+	/// # Panics
+	///
+	/// If the prefix of `C` is [`RESERVED_PREFIX`].
+	pub fn persistent<C: PersistentComponent<D> + 'static>(mut self) -> Self {
+		self.components.insert(
+			checked_prefix(C::PREFIX.to_owned()),
+			ComponentRoute::Persistent(Box::new(PersistentHandler::<C>(PhantomData))),
+		);
+		self
+	}
+
+	/// Verifies the custom ids of signed persistent components with this key.
+	pub fn signing_key(mut self, key: SigningKey) -> Self {
+		self.signing_key = Some(key);
+		self
+	}
+
+	/// # Panics
+	///
+	/// If `prefix` is [`RESERVED_PREFIX`].
+	pub fn modal(
+		mut self,
+		prefix: impl Into<String>,
+		handler: impl ModalHandler<D> + 'static,
+	) -> Self {
+		let prefix = checked_prefix(prefix.into());
+		self.modals.insert(prefix, Box::new(handler));
+		self
+	}
+
+	pub async fn dispatch(&self, ctx: Context, int: Interaction, data: &D) -> Result<()> {
+		match int {
+			Interaction::Command(_) | Interaction::Autocomplete(_) => {
+				T::dispatch(ctx, int, data).await
+			}
+			Interaction::Component(cint) => {
+				let prefix = custom_id_prefix(&cint.data.custom_id);
+
+				if prefix == RESERVED_PREFIX {
+					return Ok(());
+				}
+
+				match self.components.get(prefix) {
+					Some(ComponentRoute::Handler(handler)) => handler.handle(ctx, cint, data).await,
+					Some(ComponentRoute::Persistent(handler)) => {
+						handler
+							.handle(ctx, cint, data, self.signing_key.as_ref())
+							.await
+					}
+					None => Ok(()),
+				}
+			}
+			Interaction::Modal(mint) => {
+				let prefix = custom_id_prefix(&mint.data.custom_id);
+
+				if prefix == RESERVED_PREFIX {
+					return Ok(());
+				}
+
+				match self.modals.get(prefix) {
+					Some(handler) => handler.handle(ctx, mint, data).await,
+					None => Ok(()),
+				}
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+impl<T: CommandTree> Default for InteractionRouter<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+fn checked_prefix(prefix: String) -> String {
+	assert!(
+		prefix != RESERVED_PREFIX,
+		"the custom_id prefix `{RESERVED_PREFIX}` is reserved for serein's own components"
+	);
+	prefix
+}
+
+fn custom_id_prefix(custom_id: &str) -> &str {
+	custom_id
+		.split(CUSTOM_ID_SEPARATOR)
+		.next()
+		.unwrap_or(custom_id)
+}
+
+#[cfg(test)]
+mod tests {
+	use async_trait::async_trait;
+	use serenity::all::{ComponentInteraction, CreateCommand};
+
+	use super::*;
+
+	struct Tree;
+
+	#[async_trait]
+	impl CommandTree for Tree {
+		async fn dispatch(_ctx: Context, _int: Interaction, _data: &()) -> Result<()> {
+			Ok(())
+		}
+
+		fn create() -> Vec<CreateCommand> {
+			vec![]
+		}
+	}
+
+	struct Noop;
+
+	#[async_trait]
+	impl ComponentHandler for Noop {
+		async fn handle(
+			&self,
+			_ctx: Context,
+			_int: ComponentInteraction,
+			_data: &(),
+		) -> Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "is reserved")]
+	fn reserved_prefix_is_rejected() {
+		let _ = InteractionRouter::<Tree>::new().component(RESERVED_PREFIX, Noop);
+	}
+
+	#[test]
+	fn other_prefixes_are_accepted() {
+		let router = InteractionRouter::<Tree>::new().component("sereins", Noop);
+		assert!(router.components.contains_key("sereins"));
+	}
+}
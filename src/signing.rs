@@ -0,0 +1,172 @@
+use std::fmt::{self, Debug, Write};
+
+use ring::hmac;
+
+use crate::component::CUSTOM_ID_SEPARATOR;
+use crate::error::{Error, Result};
+
+/// Bytes of the HMAC-SHA256 tag that are kept, each encoded as two hex digits.
+pub const SIGNATURE_BYTES: usize = 8;
+pub const SIGNATURE_LEN: usize = SIGNATURE_BYTES * 2;
+
+/// The key that custom ids of components derived with `#[serein(signed)]` are signed with. Ids
+/// signed with one key don't verify with another, so keep it the same across restarts for
+/// persistent components.
+#[derive(Clone)]
+pub struct SigningKey(hmac::Key);
+
+impl SigningKey {
+	pub fn new(secret: impl AsRef<[u8]>) -> Self {
+		Self(hmac::Key::new(hmac::HMAC_SHA256, secret.as_ref()))
+	}
+
+	/// Appends a truncated signature of the custom id as its last segment.
+	pub fn sign_custom_id(&self, mut custom_id: String) -> String {
+		let signature = self.signature(&custom_id);
+
+		custom_id.push(CUSTOM_ID_SEPARATOR);
+		custom_id.push_str(&signature);
+
+		custom_id
+	}
+
+	/// Checks and strips the signature appended by [`sign_custom_id`](Self::sign_custom_id).
+	pub fn verify_custom_id<'a>(&self, custom_id: &'a str) -> Result<&'a str> {
+		let (payload, signature) = custom_id
+			.rsplit_once(CUSTOM_ID_SEPARATOR)
+			.ok_or(Error::BadSignature)?;
+
+		let expected = self.signature(payload);
+
+		// Compared in constant time so the signature can't be guessed byte by byte.
+		let mismatch = signature.len() != expected.len()
+			|| signature
+				.bytes()
+				.zip(expected.bytes())
+				.fold(0, |acc, (a, b)| acc | (a ^ b))
+				!= 0;
+
+		if mismatch {
+			return Err(Error::BadSignature);
+		}
+
+		Ok(payload)
+	}
+
+	fn signature(&self, payload: &str) -> String {
+		let tag = hmac::sign(&self.0, payload.as_bytes());
+
+		let mut signature = String::with_capacity(SIGNATURE_LEN);
+		for byte in &tag.as_ref()[..SIGNATURE_BYTES] {
+			let _ = write!(signature, "{byte:02x}");
+		}
+
+		signature
+	}
+}
+
+impl Debug for SigningKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SigningKey").finish_non_exhaustive()
+	}
+}
+
+// Used by the derives, which fail without a key.
+
+pub fn sign_custom_id(custom_id: String, key: Option<&SigningKey>) -> Result<String> {
+	let key = key.ok_or(Error::NoSigningKey)?;
+	Ok(key.sign_custom_id(custom_id))
+}
+
+pub fn verify_custom_id<'a>(custom_id: &'a str, key: Option<&SigningKey>) -> Result<&'a str> {
+	key.ok_or(Error::NoSigningKey)?.verify_custom_id(custom_id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::component::{Button, CustomId};
+
+	#[derive(Button, Debug, PartialEq)]
+	#[serein(signed)]
+	struct Grant {
+		role: u64,
+	}
+
+	#[test]
+	fn signed_ids_round_trip() {
+		let key = SigningKey::new("secret");
+
+		let custom_id = Grant { role: 5 }.to_custom_id(Some(&key)).unwrap();
+		assert_eq!(custom_id.len(), "Grant:5:".len() + SIGNATURE_LEN);
+		assert!(Grant::matches_custom_id(&custom_id));
+		assert_eq!(
+			Grant::from_custom_id(&custom_id, Some(&key)).unwrap(),
+			Grant { role: 5 }
+		);
+	}
+
+	#[test]
+	fn tampered_ids_are_rejected() {
+		let key = SigningKey::new("secret");
+		let custom_id = Grant { role: 5 }.to_custom_id(Some(&key)).unwrap();
+		let (_, signature) = custom_id.rsplit_once(':').unwrap();
+
+		let forged = format!("Grant:6:{signature}");
+		assert!(matches!(
+			Grant::from_custom_id(&forged, Some(&key)),
+			Err(Error::BadSignature)
+		));
+
+		let mut flipped = custom_id.clone();
+		let last = flipped.pop().unwrap();
+		flipped.push(if last == '0' { '1' } else { '0' });
+
+		for custom_id in [
+			&flipped,
+			&custom_id[..custom_id.len() - 1],
+			"Grant:5",
+			"Grant",
+		] {
+			assert!(
+				matches!(
+					Grant::from_custom_id(custom_id, Some(&key)),
+					Err(Error::BadSignature)
+				),
+				"{custom_id}"
+			);
+		}
+	}
+
+	#[test]
+	fn other_keys_are_rejected() {
+		let custom_id = Grant { role: 5 }
+			.to_custom_id(Some(&SigningKey::new("secret")))
+			.unwrap();
+
+		assert!(matches!(
+			Grant::from_custom_id(&custom_id, Some(&SigningKey::new("other"))),
+			Err(Error::BadSignature)
+		));
+	}
+
+	#[test]
+	fn missing_keys_are_rejected() {
+		let custom_id = Grant { role: 5 }
+			.to_custom_id(Some(&SigningKey::new("secret")))
+			.unwrap();
+
+		assert!(matches!(
+			Grant { role: 5 }.to_custom_id(None),
+			Err(Error::NoSigningKey)
+		));
+		assert!(matches!(
+			Grant::from_custom_id(&custom_id, None),
+			Err(Error::NoSigningKey)
+		));
+		assert!(matches!(
+			Grant { role: 5 }.create(None),
+			Err(Error::NoSigningKey)
+		));
+	}
+}
@@ -1,35 +1,93 @@
 use async_trait::async_trait;
-use serenity::all::{Context, CreateCommand, CreateCommandOption, Interaction};
+use serenity::all::{
+	Context, CreateCommand, CreateCommandOption, GuildId, Http, Interaction, ResolvedOption,
+};
 
+use crate::ctx::Ctx;
 use crate::error::Result;
+use crate::sync::{SyncReport, SyncScope, sync_commands};
 
 pub use serein_macros::{Command, CommandTree, SubCommand, SubSubCommand};
 
 #[async_trait]
-pub trait CommandTree {
-	async fn dispatch(ctx: Context, int: Interaction) -> Result<()>;
+pub trait CommandTree<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
 	fn create() -> Vec<CreateCommand>;
+
+	async fn register_global(http: &Http) -> Result<Vec<serenity::all::Command>> {
+		let commands = serenity::all::Command::set_global_commands(http, Self::create())
+			.await
+			.map_err(Box::new)?;
+
+		Ok(commands)
+	}
+
+	async fn register_in_guild(
+		http: &Http,
+		guild_id: GuildId,
+	) -> Result<Vec<serenity::all::Command>> {
+		let commands = guild_id
+			.set_commands(http, Self::create())
+			.await
+			.map_err(Box::new)?;
+
+		Ok(commands)
+	}
+
+	async fn sync_global(http: &Http) -> Result<SyncReport> {
+		sync_commands(http, SyncScope::Global, Self::create()).await
+	}
+
+	async fn sync_in_guild(http: &Http, guild_id: GuildId) -> Result<SyncReport> {
+		sync_commands(http, SyncScope::Guild(guild_id), Self::create()).await
+	}
 }
 
 #[async_trait]
-pub trait Command {
-	async fn dispatch(ctx: Context, int: Interaction) -> Result<()>;
+pub trait Command<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
 	fn create(name: impl Into<String>) -> CreateCommand;
 }
 
 #[async_trait]
-pub trait SubCommand {
-	async fn dispatch(ctx: Context, int: Interaction) -> Result<()>;
+pub trait SubCommand<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
 	fn create(name: impl Into<String>, desc: impl Into<String>) -> CreateCommandOption;
 }
 
 #[async_trait]
-pub trait SubSubCommand {
-	async fn dispatch(ctx: Context, int: Interaction) -> Result<()>;
+pub trait SubSubCommand<D: Send + Sync = ()> {
+	async fn dispatch(ctx: Context, int: Interaction, data: &D) -> Result<()>;
 	fn create(name: impl Into<String>, desc: impl Into<String>) -> CreateCommandOption;
 }
 
+/// The handler of a leaf command, i.e. a struct deriving [`Command`], [`SubCommand`],
+/// [`SubSubCommand`], [`MessageCommand`](crate::context_menu::MessageCommand) or
+/// [`UserCommand`](crate::context_menu::UserCommand). The derived `dispatch` parses the options
+/// into `Self` and then calls [`CommandHandler::handle`] on it.
+///
+/// `D` is the shared data type set with `#[serein(data = MyData)]` on the derive, `()` if unset.
+#[diagnostic::on_unimplemented(
+	message = "`{Self}` has no command handler",
+	label = "missing `CommandHandler` implementation",
+	note = "implement `serein::slash::CommandHandler` for `{Self}` to handle the command"
+)]
 #[async_trait]
-pub trait CommandHandler {
-	async fn handle(&self, ctx: Context, int: Interaction) -> Result<()>;
+pub trait CommandHandler<D: Send + Sync = ()> {
+	async fn handle(&self, ctx: Ctx, data: &D) -> Result<()>;
 }
+
+/// Implemented by the command derives for structs with fields marked
+/// `#[serein(autocomplete = handler)]`. The partial options have a field for each field of the
+/// command, holding the option if it was already filled in and parsed.
+pub trait PartialOptions {
+	type Partial;
+
+	fn partial_from_options(opts: &[ResolvedOption]) -> Self::Partial;
+}
+
+/// The partial options of the command `T`, passed to its autocomplete handlers. Each handler is
+/// called for its own field as
+/// `handler(ctx: &Ctx, partial: &Partial<T>, value: &str, data: &D) -> Result<Vec<AutocompleteChoice>>`
+/// with the value typed in so far, and at most the first 25 of the returned choices are sent back.
+pub type Partial<T> = <T as PartialOptions>::Partial;
@@ -0,0 +1,467 @@
+use serde_json::{Map, Value};
+use serenity::all::{Command, CreateCommand, GuildId, Http};
+
+use crate::error::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncScope {
+	Global,
+	Guild(GuildId),
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+	pub created: Vec<Command>,
+	pub edited: Vec<Command>,
+	pub deleted: Vec<Command>,
+	pub unchanged: Vec<Command>,
+}
+
+impl SyncReport {
+	pub fn is_noop(&self) -> bool {
+		self.created.is_empty() && self.edited.is_empty() && self.deleted.is_empty()
+	}
+}
+
+// Top-level fields that are always compared.
+const COMPARED_FIELDS: &[&str] = &[
+	"name",
+	"name_localizations",
+	"description",
+	"description_localizations",
+	"options",
+	"default_member_permissions",
+	"nsfw",
+];
+
+// Fields that Discord fills in with a default when they're unset, so that unset compares equal to
+// the default, and removing a local value resets the remote one.
+const DEFAULTED_FIELDS: &[(&str, &[u64])] =
+	&[("integration_types", &[0]), ("contexts", &[0, 1, 2])];
+
+pub async fn sync_commands(
+	http: &Http,
+	scope: SyncScope,
+	commands: Vec<CreateCommand>,
+) -> Result<SyncReport> {
+	let mut remote = match scope {
+		SyncScope::Global => http.get_global_commands_with_localizations().await,
+		SyncScope::Guild(guild_id) => http.get_guild_commands_with_localizations(guild_id).await,
+	}
+	.map_err(Box::new)?;
+
+	let mut report = SyncReport::default();
+
+	for command in commands {
+		let local = to_value(&command)?;
+
+		let position = remote.iter().position(|existing| {
+			to_value(existing).is_ok_and(|existing| command_key(&existing) == command_key(&local))
+		});
+
+		match position {
+			Some(position) => {
+				let existing = remote.swap_remove(position);
+
+				if commands_equal(&local, &to_value(&existing)?) {
+					report.unchanged.push(existing);
+					continue;
+				}
+
+				let edited = match scope {
+					SyncScope::Global => http.edit_global_command(existing.id, &command).await,
+					SyncScope::Guild(guild_id) => {
+						http.edit_guild_command(guild_id, existing.id, &command)
+							.await
+					}
+				}
+				.map_err(Box::new)?;
+
+				report.edited.push(edited);
+			}
+			None => {
+				let created = match scope {
+					SyncScope::Global => http.create_global_command(&command).await,
+					SyncScope::Guild(guild_id) => {
+						http.create_guild_command(guild_id, &command).await
+					}
+				}
+				.map_err(Box::new)?;
+
+				report.created.push(created);
+			}
+		}
+	}
+
+	for existing in remote {
+		match scope {
+			SyncScope::Global => http.delete_global_command(existing.id).await,
+			SyncScope::Guild(guild_id) => http.delete_guild_command(guild_id, existing.id).await,
+		}
+		.map_err(Box::new)?;
+
+		report.deleted.push(existing);
+	}
+
+	Ok(report)
+}
+
+fn to_value(value: &impl serde::Serialize) -> Result<Value> {
+	serde_json::to_value(value).map_err(|err| Box::new(serenity::Error::from(err)).into())
+}
+
+fn command_key(command: &Value) -> (Option<&str>, u64) {
+	let name = command.get("name").and_then(Value::as_str);
+	let kind = command.get("type").and_then(Value::as_u64).unwrap_or(1);
+	(name, kind)
+}
+
+fn commands_equal(local: &Value, remote: &Value) -> bool {
+	if command_key(local) != command_key(remote) {
+		return false;
+	}
+
+	let fields_equal = |field: &str| {
+		let local = local.get(field).map(normalize).unwrap_or(Value::Null);
+		let remote = remote.get(field).map(normalize).unwrap_or(Value::Null);
+		local == remote
+	};
+
+	let defaulted_fields_equal = |field: &str, default: &[u64]| {
+		let or_default = |value: Option<&Value>| match value.map(normalize) {
+			Some(value) if !value.is_null() => value,
+			_ => normalize(&Value::from(default)),
+		};
+
+		or_default(local.get(field)) == or_default(remote.get(field))
+	};
+
+	COMPARED_FIELDS.iter().all(|field| fields_equal(field))
+		&& DEFAULTED_FIELDS
+			.iter()
+			.all(|(field, default)| defaulted_fields_equal(field, default))
+}
+
+// Collapses the different ways Discord and the builders spell "not set" (missing, `null`, `false`,
+// `""`, `[]`, `{}`) into `null`, and makes `1` and `1.0` compare equal.
+fn normalize(value: &Value) -> Value {
+	match value {
+		Value::Bool(false) => Value::Null,
+		Value::String(string) if string.is_empty() => Value::Null,
+		Value::Number(number) => match number.as_f64() {
+			Some(number) => Value::from(number),
+			None => Value::Number(number.clone()),
+		},
+		Value::Array(array) if array.is_empty() => Value::Null,
+		Value::Array(array) => Value::Array(array.iter().map(normalize).collect()),
+		Value::Object(object) => {
+			let object: Map<String, Value> = object
+				.iter()
+				.map(|(key, value)| (key.clone(), normalize(value)))
+				.filter(|(_, value)| !value.is_null())
+				.collect();
+
+			if object.is_empty() {
+				Value::Null
+			} else {
+				Value::Object(object)
+			}
+		}
+		value => value.clone(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{Arc, Mutex};
+
+	use serde_json::json;
+	use serenity::all::{ApplicationId, CreateCommandOption, HttpBuilder};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::{TcpListener, TcpStream};
+
+	use super::*;
+
+	#[test]
+	fn unset_fields_equal_missing_fields() {
+		let local = json!({ "name": "ping", "description": "Ping" });
+		let remote = json!({
+			"name": "ping",
+			"description": "Ping",
+			"nsfw": false,
+			"name_localizations": {},
+			"description_localizations": null,
+			"options": [],
+			"default_member_permissions": "",
+		});
+
+		assert!(commands_equal(&local, &remote));
+		assert!(commands_equal(&remote, &local));
+	}
+
+	#[test]
+	fn set_fields_differ_from_missing_fields() {
+		let local = json!({ "name": "ping", "description": "Ping", "nsfw": true });
+		let remote = json!({ "name": "ping", "description": "Ping" });
+
+		assert!(!commands_equal(&local, &remote));
+	}
+
+	#[test]
+	fn integers_equal_floats() {
+		let local = json!({
+			"name": "roll",
+			"description": "Roll",
+			"options": [{ "type": 4, "name": "sides", "description": "Sides", "min_value": 1 }],
+		});
+		let remote = json!({
+			"name": "roll",
+			"description": "Roll",
+			"options": [{ "type": 4, "name": "sides", "description": "Sides", "min_value": 1.0 }],
+		});
+
+		assert!(commands_equal(&local, &remote));
+	}
+
+	#[test]
+	fn option_order_matters() {
+		let local = json!({
+			"name": "add",
+			"description": "Add",
+			"options": [
+				{ "type": 4, "name": "a", "description": "A" },
+				{ "type": 4, "name": "b", "description": "B" },
+			],
+		});
+		let remote = json!({
+			"name": "add",
+			"description": "Add",
+			"options": [
+				{ "type": 4, "name": "b", "description": "B" },
+				{ "type": 4, "name": "a", "description": "A" },
+			],
+		});
+
+		assert!(!commands_equal(&local, &remote));
+	}
+
+	#[test]
+	fn unset_contexts_equal_defaults() {
+		let remote = json!({ "name": "ping", "description": "Ping", "contexts": [0, 1, 2] });
+
+		let unset = json!({ "name": "ping", "description": "Ping" });
+		assert!(commands_equal(&unset, &remote));
+
+		let null = json!({ "name": "ping", "description": "Ping", "contexts": null });
+		assert!(commands_equal(&unset, &null));
+
+		let set = json!({ "name": "ping", "description": "Ping", "contexts": [0] });
+		assert!(!commands_equal(&set, &remote));
+	}
+
+	#[test]
+	fn removed_contexts_differ_from_remote() {
+		let local = json!({ "name": "ping", "description": "Ping" });
+
+		let remote = json!({ "name": "ping", "description": "Ping", "contexts": [0] });
+		assert!(!commands_equal(&local, &remote));
+
+		let remote = json!({ "name": "ping", "description": "Ping", "integration_types": [1] });
+		assert!(!commands_equal(&local, &remote));
+
+		let remote = json!({ "name": "ping", "description": "Ping", "integration_types": [0] });
+		assert!(commands_equal(&local, &remote));
+	}
+
+	#[test]
+	fn normalize_collapses_unset_values() {
+		for value in [
+			json!(false),
+			json!(""),
+			json!([]),
+			json!({}),
+			json!({ "a": null }),
+		] {
+			assert_eq!(normalize(&value), Value::Null, "{value}");
+		}
+
+		assert_eq!(normalize(&json!(1)), normalize(&json!(1.0)));
+		assert_eq!(normalize(&json!(true)), json!(true));
+	}
+
+	#[tokio::test]
+	async fn sync_creates_edits_and_deletes() {
+		let remote = json!([
+			remote_command(1, "ping", "Ping"),
+			remote_command(2, "echo", "Old description"),
+			remote_command(3, "stale", "Stale"),
+		]);
+		let (http, requests) = stub_http(remote).await;
+
+		let commands = vec![
+			CreateCommand::new("ping").description("Ping"),
+			CreateCommand::new("echo")
+				.description("Echo")
+				.add_option(CreateCommandOption::new(
+					serenity::all::CommandOptionType::String,
+					"text",
+					"Text",
+				)),
+			CreateCommand::new("new").description("New"),
+		];
+
+		let report = sync_commands(&http, SyncScope::Global, commands)
+			.await
+			.unwrap();
+
+		let names = |commands: &[Command]| {
+			commands
+				.iter()
+				.map(|command| command.name.clone())
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(names(&report.unchanged), ["ping"]);
+		assert_eq!(names(&report.edited), ["echo"]);
+		assert_eq!(names(&report.created), ["new"]);
+		assert_eq!(names(&report.deleted), ["stale"]);
+
+		assert_eq!(
+			*requests.lock().unwrap(),
+			[
+				"GET /api/v10/applications/1/commands",
+				"PATCH /api/v10/applications/1/commands/2",
+				"POST /api/v10/applications/1/commands",
+				"DELETE /api/v10/applications/1/commands/3",
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn sync_without_changes_is_noop() {
+		let remote = json!([remote_command(1, "ping", "Ping")]);
+		let (http, requests) = stub_http(remote).await;
+
+		let commands = vec![CreateCommand::new("ping").description("Ping")];
+
+		let report = sync_commands(&http, SyncScope::Guild(GuildId::new(5)), commands)
+			.await
+			.unwrap();
+
+		assert!(report.is_noop());
+		assert_eq!(requests.lock().unwrap().len(), 1);
+	}
+
+	fn remote_command(id: u64, name: &str, description: &str) -> Value {
+		json!({
+			"id": id.to_string(),
+			"application_id": "1",
+			"version": "1",
+			"type": 1,
+			"name": name,
+			"description": description,
+			"default_member_permissions": null,
+			"nsfw": false,
+		})
+	}
+
+	/// Serves the Discord command endpoints from a local listener: `GET` returns `remote`, `POST`
+	/// and `PATCH` echo the sent command back and `DELETE` returns nothing. The method and path of
+	/// every request are recorded, without the query.
+	async fn stub_http(remote: Value) -> (Http, Arc<Mutex<Vec<String>>>) {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		let requests = Arc::new(Mutex::new(Vec::new()));
+
+		let recorded = Arc::clone(&requests);
+		tokio::spawn(async move {
+			loop {
+				let (stream, _) = listener.accept().await.unwrap();
+				serve(stream, &remote, &recorded).await;
+			}
+		});
+
+		let http = HttpBuilder::new("token")
+			.proxy(format!("http://{addr}"))
+			.ratelimiter_disabled(true)
+			.application_id(ApplicationId::new(1))
+			.build();
+
+		(http, requests)
+	}
+
+	async fn serve(mut stream: TcpStream, remote: &Value, requests: &Mutex<Vec<String>>) {
+		let mut buf = Vec::new();
+
+		let header_end = loop {
+			let mut chunk = [0; 4096];
+			let read = stream.read(&mut chunk).await.unwrap();
+			if read == 0 {
+				return;
+			}
+			buf.extend_from_slice(&chunk[..read]);
+
+			if let Some(i) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+				break i + 4;
+			}
+		};
+
+		let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+		let content_length = head
+			.lines()
+			.find_map(|line| {
+				let (name, value) = line.split_once(':')?;
+				name.eq_ignore_ascii_case("content-length")
+					.then(|| value.trim().parse::<usize>().unwrap())
+			})
+			.unwrap_or(0);
+
+		while buf.len() < header_end + content_length {
+			let mut chunk = [0; 4096];
+			let read = stream.read(&mut chunk).await.unwrap();
+			buf.extend_from_slice(&chunk[..read]);
+		}
+
+		let mut request_line = head.lines().next().unwrap().split(' ');
+		let method = request_line.next().unwrap();
+		let path = request_line.next().unwrap().split('?').next().unwrap();
+		requests.lock().unwrap().push(format!("{method} {path}"));
+
+		let response = match method {
+			"GET" => Some(remote.clone()),
+			"POST" | "PATCH" => {
+				let mut command: Value = serde_json::from_slice(&buf[header_end..]).unwrap();
+				let id = path
+					.rsplit('/')
+					.next()
+					.unwrap()
+					.parse::<u64>()
+					.unwrap_or(100);
+				let name = command["name"].as_str().unwrap().to_owned();
+				let description = command["description"].as_str().unwrap().to_owned();
+
+				let remote = remote_command(id, &name, &description);
+				for (key, value) in remote.as_object().unwrap() {
+					command
+						.as_object_mut()
+						.unwrap()
+						.entry(key)
+						.or_insert(value.clone());
+				}
+				Some(command)
+			}
+			_ => None,
+		};
+
+		let (status, body) = match response {
+			Some(body) => ("200 OK", body.to_string()),
+			None => ("204 No Content", String::new()),
+		};
+
+		let response = format!(
+			"HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+			body.len()
+		);
+		stream.write_all(response.as_bytes()).await.unwrap();
+	}
+}
@@ -0,0 +1,415 @@
+use std::time::Duration;
+
+use serenity::all::{
+	ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+	EditInteractionResponse, Message,
+};
+use serenity::futures::StreamExt;
+use tokio::time::Instant;
+
+use crate::collector::{ComponentCollector, FromComponent};
+use crate::ctx::Ctx;
+use crate::error::{Error, Result};
+use crate::router::RESERVED_PREFIX;
+use crate::signing::SigningKey;
+
+#[derive(Clone, Debug)]
+pub enum Page {
+	Content(String),
+	Embed(Box<CreateEmbed>),
+}
+
+impl Page {
+	fn to_message(&self) -> CreateInteractionResponseMessage {
+		match self {
+			Self::Content(content) => CreateInteractionResponseMessage::new()
+				.content(content)
+				.embeds(vec![]),
+			Self::Embed(embed) => CreateInteractionResponseMessage::new()
+				.content("")
+				.embeds(vec![(**embed).clone()]),
+		}
+	}
+
+	fn to_edit(&self) -> EditInteractionResponse {
+		match self {
+			Self::Content(content) => EditInteractionResponse::new()
+				.content(content)
+				.embeds(vec![]),
+			Self::Embed(embed) => EditInteractionResponse::new()
+				.content("")
+				.embeds(vec![(**embed).clone()]),
+		}
+	}
+}
+
+impl From<String> for Page {
+	fn from(content: String) -> Self {
+		Self::Content(content)
+	}
+}
+
+impl From<&str> for Page {
+	fn from(content: &str) -> Self {
+		Self::Content(content.to_owned())
+	}
+}
+
+impl From<CreateEmbed> for Page {
+	fn from(embed: CreateEmbed) -> Self {
+		Self::Embed(Box::new(embed))
+	}
+}
+
+/// Responds with the first of several pages and lets the invoking user flip through them with
+/// buttons, until they don't click one for the length of the timeout. Other users who click are
+/// told, in an ephemeral message, that they can't.
+#[derive(Clone, Debug)]
+pub struct Paginator {
+	pages: Vec<Page>,
+	timeout: Duration,
+	ephemeral: bool,
+}
+
+impl Paginator {
+	pub fn new<P: Into<Page>>(pages: impl IntoIterator<Item = P>) -> Self {
+		Self {
+			pages: pages.into_iter().map(Into::into).collect(),
+			timeout: Duration::from_secs(120),
+			ephemeral: false,
+		}
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+		self.ephemeral = ephemeral;
+		self
+	}
+
+	pub async fn run(self, ctx: &Ctx) -> Result<()> {
+		if self.pages.is_empty() {
+			return Err(Error::NoPages);
+		}
+
+		let mut index = 0;
+
+		let message = self.send(ctx, index).await?;
+
+		if self.pages.len() == 1 {
+			return Ok(());
+		}
+
+		let invoker = ctx.interaction().user.id;
+
+		// Clicks of other users are collected too, to tell them they can't navigate.
+		let mut clicks = ComponentCollector::<PageNav>::new(ctx.context(), &message)
+			.interaction_token(&ctx.interaction().token)
+			.without_timeout()
+			.disable_on_end(false)
+			.stream()
+			.boxed();
+
+		// Unlike the timeout of a collector, this one restarts with every click of the invoker.
+		let mut deadline = Instant::now() + self.timeout;
+
+		let result = async {
+			while let Ok(Some(click)) = tokio::time::timeout_at(deadline, clicks.next()).await {
+				let (nav, int) = click?;
+
+				let response = if int.user.id == invoker {
+					deadline = Instant::now() + self.timeout;
+					index = nav.apply(index, self.pages.len());
+
+					let update = self.pages[index]
+						.to_message()
+						.components(self.components(index, false));
+
+					CreateInteractionResponse::UpdateMessage(update)
+				} else {
+					let message = CreateInteractionResponseMessage::new()
+						.content("Only the user who ran the command can turn the pages.")
+						.ephemeral(true);
+
+					CreateInteractionResponse::Message(message)
+				};
+
+				int.create_response(ctx.http(), response)
+					.await
+					.map_err(Box::new)?;
+			}
+
+			Ok(())
+		}
+		.await;
+
+		// The buttons are disabled even when collecting failed, so they don't linger unanswered.
+		let edit = self.pages[index]
+			.to_edit()
+			.components(self.components(index, true));
+
+		let edited = ctx.edit_response(edit).await;
+
+		result.and(edited.map(drop))
+	}
+
+	async fn send(&self, ctx: &Ctx, index: usize) -> Result<Message> {
+		let components = match self.pages.len() {
+			1 => vec![],
+			_ => self.components(index, false),
+		};
+
+		if ctx.is_acknowledged() {
+			return ctx
+				.edit_response(self.pages[index].to_edit().components(components))
+				.await;
+		}
+
+		let message = self.pages[index]
+			.to_message()
+			.components(components)
+			.ephemeral(self.ephemeral);
+
+		ctx.respond(CreateInteractionResponse::Message(message))
+			.await?;
+
+		let message = ctx
+			.interaction()
+			.get_response(ctx.http())
+			.await
+			.map_err(Box::new)?;
+
+		Ok(message)
+	}
+
+	fn components(&self, index: usize, disabled: bool) -> Vec<CreateActionRow> {
+		let last = self.pages.len() - 1;
+
+		let button = |nav: PageNav, label: &str, at_edge: bool| {
+			CreateButton::new(nav.custom_id())
+				.label(label)
+				.style(ButtonStyle::Secondary)
+				.disabled(disabled || at_edge)
+		};
+
+		let counter = CreateButton::new(widget_custom_id("page", "counter"))
+			.label(format!("{}/{}", index + 1, self.pages.len()))
+			.style(ButtonStyle::Secondary)
+			.disabled(true);
+
+		vec![CreateActionRow::Buttons(vec![
+			button(PageNav::First, "⏮", index == 0),
+			button(PageNav::Prev, "◀", index == 0),
+			counter,
+			button(PageNav::Next, "▶", index == last),
+			button(PageNav::Last, "⏭", index == last),
+		])]
+	}
+}
+
+fn widget_custom_id(widget: &str, action: &str) -> String {
+	format!("{RESERVED_PREFIX}:{widget}:{action}")
+}
+
+fn widget_action<'a>(custom_id: &'a str, widget: &str) -> Option<&'a str> {
+	custom_id
+		.strip_prefix(RESERVED_PREFIX)?
+		.strip_prefix(':')?
+		.strip_prefix(widget)?
+		.strip_prefix(':')
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PageNav {
+	First,
+	Prev,
+	Next,
+	Last,
+}
+
+impl PageNav {
+	fn custom_id(&self) -> String {
+		let action = match self {
+			Self::First => "first",
+			Self::Prev => "prev",
+			Self::Next => "next",
+			Self::Last => "last",
+		};
+
+		widget_custom_id("page", action)
+	}
+
+	fn apply(&self, index: usize, len: usize) -> usize {
+		match self {
+			Self::First => 0,
+			Self::Prev => index.saturating_sub(1),
+			Self::Next => (index + 1).min(len - 1),
+			Self::Last => len - 1,
+		}
+	}
+}
+
+impl FromComponent for PageNav {
+	fn accepts_component(int: &ComponentInteraction) -> bool {
+		widget_action(&int.data.custom_id, "page").is_some()
+	}
+
+	fn try_from_component(int: &ComponentInteraction, _key: Option<&SigningKey>) -> Result<Self> {
+		match widget_action(&int.data.custom_id, "page") {
+			Some("first") => Ok(Self::First),
+			Some("prev") => Ok(Self::Prev),
+			Some("next") => Ok(Self::Next),
+			Some("last") => Ok(Self::Last),
+			_ => Err(Error::BadCustomId),
+		}
+	}
+}
+
+/// Asks the invoking user to confirm an action, with an ephemeral message and Confirm/Cancel
+/// buttons. Cancelling or not answering before the timeout both count as a no.
+#[derive(Clone, Debug)]
+pub struct Confirm {
+	prompt: String,
+	confirm_label: String,
+	cancel_label: String,
+	timeout: Duration,
+}
+
+impl Confirm {
+	pub fn new(prompt: impl Into<String>) -> Self {
+		Self {
+			prompt: prompt.into(),
+			confirm_label: "Confirm".to_owned(),
+			cancel_label: "Cancel".to_owned(),
+			timeout: Duration::from_secs(60),
+		}
+	}
+
+	pub fn confirm_label(mut self, label: impl Into<String>) -> Self {
+		self.confirm_label = label.into();
+		self
+	}
+
+	pub fn cancel_label(mut self, label: impl Into<String>) -> Self {
+		self.cancel_label = label.into();
+		self
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	pub async fn run(self, ctx: &Ctx) -> Result<bool> {
+		let components = vec![CreateActionRow::Buttons(vec![
+			CreateButton::new(widget_custom_id("confirm", "yes"))
+				.label(&self.confirm_label)
+				.style(ButtonStyle::Danger),
+			CreateButton::new(widget_custom_id("confirm", "no"))
+				.label(&self.cancel_label)
+				.style(ButtonStyle::Secondary),
+		])];
+
+		// After a defer the initial response is taken, so the prompt becomes a followup.
+		let followup = ctx.is_acknowledged();
+
+		let message = if followup {
+			let followup = CreateInteractionResponseFollowup::new()
+				.content(&self.prompt)
+				.components(components)
+				.ephemeral(true);
+
+			ctx.followup(followup).await?
+		} else {
+			let message = CreateInteractionResponseMessage::new()
+				.content(&self.prompt)
+				.components(components)
+				.ephemeral(true);
+
+			ctx.respond(CreateInteractionResponse::Message(message))
+				.await?;
+
+			ctx.interaction()
+				.get_response(ctx.http())
+				.await
+				.map_err(Box::new)?
+		};
+
+		let click = ctx
+			.collect_components::<ConfirmAnswer>(&message)
+			.timeout(self.timeout)
+			.disable_on_end(false)
+			.stream()
+			.boxed()
+			.next()
+			.await;
+
+		let Some(click) = click else {
+			let content = format!("{}\n-# Timed out.", self.prompt);
+
+			if followup {
+				let edit = CreateInteractionResponseFollowup::new()
+					.content(content)
+					.components(vec![]);
+
+				ctx.interaction()
+					.edit_followup(ctx.http(), message.id, edit)
+					.await
+					.map_err(Box::new)?;
+			} else {
+				let edit = EditInteractionResponse::new()
+					.content(content)
+					.components(vec![]);
+
+				ctx.edit_response(edit).await?;
+			}
+
+			return Ok(false);
+		};
+
+		let (answer, int) = click?;
+
+		let outcome = match answer {
+			ConfirmAnswer::Yes => "Confirmed.",
+			ConfirmAnswer::No => "Cancelled.",
+		};
+
+		let update = CreateInteractionResponseMessage::new()
+			.content(format!("{}\n-# {outcome}", self.prompt))
+			.components(vec![]);
+
+		int.create_response(ctx.http(), CreateInteractionResponse::UpdateMessage(update))
+			.await
+			.map_err(Box::new)?;
+
+		Ok(matches!(answer, ConfirmAnswer::Yes))
+	}
+}
+
+pub async fn confirm(ctx: &Ctx, prompt: impl Into<String>) -> Result<bool> {
+	Confirm::new(prompt).run(ctx).await
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ConfirmAnswer {
+	Yes,
+	No,
+}
+
+impl FromComponent for ConfirmAnswer {
+	fn accepts_component(int: &ComponentInteraction) -> bool {
+		widget_action(&int.data.custom_id, "confirm").is_some()
+	}
+
+	fn try_from_component(int: &ComponentInteraction, _key: Option<&SigningKey>) -> Result<Self> {
+		match widget_action(&int.data.custom_id, "confirm") {
+			Some("yes") => Ok(Self::Yes),
+			Some("no") => Ok(Self::No),
+			_ => Err(Error::BadCustomId),
+		}
+	}
+}